@@ -16,6 +16,7 @@ use linera_sdk::{
     views::{RootView, View},
     Contract, ContractRuntime,
 };
+use notification::{NotificationAbi, NotificationKind};
 use review::{ReviewAbi, ReviewResponse};
 
 pub struct ActivityContract {
@@ -132,6 +133,10 @@ impl ActivityContract {
         self.runtime.application_parameters().foundation_app_id
     }
 
+    fn notification_app_id(&mut self) -> ApplicationId<NotificationAbi> {
+        self.runtime.application_parameters().notification_app_id
+    }
+
     fn feed_app_id(&mut self) -> ApplicationId<FeedAbi> {
         self.runtime.application_parameters().feed_app_id
     }
@@ -254,11 +259,37 @@ impl ActivityContract {
             self.activity_rewards(
                 activity_id,
                 author,
-                voter_users,
+                voter_users.clone(),
                 reward_amount,
                 activity.voter_reward_percent,
             )
             .await?;
+            if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+                continue;
+            }
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                author,
+                NotificationKind::ActivityWon,
+                activity_id.to_string(),
+                format!(
+                    "Your {} won place {} with reward {}",
+                    winner.object_id, winner.place, reward_amount
+                ),
+            );
+            for voter in voter_users {
+                let notification_app_id = self.notification_app_id();
+                notification::notify(
+                    &mut self.runtime,
+                    notification_app_id,
+                    voter,
+                    NotificationKind::RewardReceived,
+                    activity_id.to_string(),
+                    format!("You are rewarded for voting {}", winner.object_id),
+                );
+            }
         }
         self.reward_activity_host(activity_id).await?;
         Ok(())
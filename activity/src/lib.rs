@@ -28,6 +28,7 @@ impl ServiceAbi for ActivityAbi {
 pub struct ActivityParameters {
     pub review_app_id: ApplicationId<review::ReviewAbi>,
     pub foundation_app_id: ApplicationId<foundation::FoundationAbi>,
+    pub notification_app_id: ApplicationId<notification::NotificationAbi>,
     pub feed_app_id: ApplicationId<feed::FeedAbi>,
 }
 
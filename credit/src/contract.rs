@@ -3,7 +3,10 @@
 mod state;
 
 use self::state::Credit;
-use credit::{CreditAbi, CreditError, InstantiationArgument, Message, Operation};
+use credit::{
+    ApplicationEscrow, CreditAbi, CreditError, CreditResponse, InstantiationArgument, Message,
+    Operation,
+};
 use linera_sdk::{
     base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
     views::{RootView, View},
@@ -51,6 +54,7 @@ impl Contract for CreditContract {
                 .expect("Failed OP: set transfer callers"),
             Operation::Transfer { from, to, amount } => self
                 .on_op_transfer(from, to, amount)
+                .await
                 .expect("Failed OP: transfer"),
             Operation::TransferExt { to, amount } => self
                 .on_op_transfer_ext(to, amount)
@@ -61,6 +65,18 @@ impl Contract for CreditContract {
             Operation::Reward { owner, amount } => {
                 self.on_op_reward(owner, amount).expect("Failed OP: reward")
             }
+            Operation::Penalize { owner, amount } => self
+                .on_op_penalize(owner, amount)
+                .await
+                .expect("Failed OP: penalize"),
+            Operation::OutstandingSupply => self
+                .on_op_outstanding_supply()
+                .await
+                .expect("Failed OP: outstanding supply"),
+            Operation::Spendable { owner } => self
+                .on_op_spendable(owner)
+                .await
+                .expect("Failed OP: spendable"),
         }
     }
 
@@ -98,6 +114,10 @@ impl Contract for CreditContract {
                 .on_msg_request_subscribe()
                 .await
                 .expect("Failed MSG: subscribe"),
+            Message::Penalize { owner, amount } => self
+                .on_msg_penalize(owner, amount)
+                .await
+                .expect("Failed MSG: penalize"),
         }
     }
 
@@ -121,18 +141,18 @@ impl CreditContract {
         }
     }
 
-    fn on_op_liquidate(&mut self) -> Result<(), CreditError> {
+    fn on_op_liquidate(&mut self) -> Result<CreditResponse, CreditError> {
         self.runtime
             .prepare_message(Message::Liquidate)
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
     }
 
     fn on_op_set_reward_callers(
         &mut self,
         application_ids: Vec<ApplicationId>,
-    ) -> Result<(), CreditError> {
+    ) -> Result<CreditResponse, CreditError> {
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Err(CreditError::OperationNotAllowed);
         }
@@ -140,57 +160,134 @@ impl CreditContract {
             .prepare_message(Message::SetRewardCallers { application_ids })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
     }
 
     fn on_op_set_transfer_callers(
         &mut self,
         application_ids: Vec<ApplicationId>,
-    ) -> Result<(), CreditError> {
+    ) -> Result<CreditResponse, CreditError> {
         self.runtime
             .prepare_message(Message::SetTransferCallers { application_ids })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
+    }
+
+    /// Whitelisted application calling on the creation chain, if any
+    async fn transfer_caller(&mut self) -> Result<Option<ApplicationId>, CreditError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(None);
+        }
+        let Some(caller) = self.runtime.authenticated_caller_id() else {
+            return Ok(None);
+        };
+        if !self.state.transfer_callers.contains(&caller).await? {
+            return Ok(None);
+        }
+        Ok(Some(caller))
     }
 
-    fn on_op_transfer(
+    // Whitelisted applications on the creation chain get the transfer applied in the same
+    // transaction, so they know the credits moved before recording anything. They only move
+    // their own escrow or the signer's credits
+    async fn on_op_transfer(
         &mut self,
         from: Owner,
         to: Owner,
         amount: Amount,
-    ) -> Result<(), CreditError> {
+    ) -> Result<CreditResponse, CreditError> {
+        if let Some(caller) = self.transfer_caller().await? {
+            if from != ApplicationEscrow::owner(caller)
+                && Some(from) != self.runtime.authenticated_signer()
+            {
+                return Err(CreditError::CallerNotAllowed);
+            }
+            self.state
+                .transfer(from, to, amount, self.runtime.system_time())
+                .await?;
+            let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+            self.runtime
+                .prepare_message(Message::Transfer { from, to, amount })
+                .with_authentication()
+                .send_to(dest);
+            return Ok(CreditResponse::Ok);
+        }
         self.runtime
             .prepare_message(Message::Transfer { from, to, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
     }
 
-    fn on_op_transfer_ext(&mut self, to: Owner, amount: Amount) -> Result<(), CreditError> {
+    fn on_op_transfer_ext(
+        &mut self,
+        to: Owner,
+        amount: Amount,
+    ) -> Result<CreditResponse, CreditError> {
         self.runtime
             .prepare_message(Message::TransferExt { to, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
     }
 
-    fn on_op_request_subscribe(&mut self) -> Result<(), CreditError> {
+    fn on_op_request_subscribe(&mut self) -> Result<CreditResponse, CreditError> {
         self.runtime
             .prepare_message(Message::RequestSubscribe)
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Ok)
     }
 
-    fn on_op_reward(&mut self, owner: Owner, amount: Amount) -> Result<(), CreditError> {
+    fn on_op_reward(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<CreditResponse, CreditError> {
         self.runtime
             .prepare_message(Message::Reward { owner, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(CreditResponse::Ok)
+    }
+
+    async fn require_reward_caller(&mut self) -> Result<(), CreditError> {
+        let caller_id = match self.runtime.authenticated_caller_id() {
+            Some(caller_id) => caller_id,
+            None => return Err(CreditError::CallerNotAllowed),
+        };
+        if !self.state.reward_callers.contains(&caller_id).await? {
+            return Err(CreditError::CallerNotAllowed);
+        }
         Ok(())
     }
 
+    async fn on_op_penalize(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<CreditResponse, CreditError> {
+        self.require_reward_caller().await?;
+        self.runtime
+            .prepare_message(Message::Penalize { owner, amount })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(CreditResponse::Ok)
+    }
+
+    async fn on_op_outstanding_supply(&mut self) -> Result<CreditResponse, CreditError> {
+        Ok(CreditResponse::OutstandingSupply(
+            self.state.outstanding_supply().await,
+        ))
+    }
+
+    async fn on_op_spendable(&mut self, owner: Owner) -> Result<CreditResponse, CreditError> {
+        Ok(CreditResponse::Spendable(
+            self.state.spendable(owner).await?,
+        ))
+    }
+
     async fn on_msg_instantiation_argument(
         &mut self,
         arg: InstantiationArgument,
@@ -227,6 +324,19 @@ impl CreditContract {
         Ok(())
     }
 
+    async fn on_msg_penalize(&mut self, owner: Owner, amount: Amount) -> Result<(), CreditError> {
+        self.state.penalize(owner, amount).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Penalize { owner, amount })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
     async fn on_msg_set_reward_callers(
         &mut self,
         application_ids: Vec<ApplicationId>,
@@ -273,6 +383,12 @@ impl CreditContract {
         to: Owner,
         amount: Amount,
     ) -> Result<(), CreditError> {
+        // Users only move their own credits, subscribers replay what the creation chain applied
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id
+            && Some(from) != self.runtime.authenticated_signer()
+        {
+            return Err(CreditError::InvalidSigner);
+        }
         self.state
             .transfer(from, to, amount, self.runtime.system_time())
             .await?;
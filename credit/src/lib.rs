@@ -1,6 +1,8 @@
 use async_graphql::{Request, Response, SimpleObject};
 use linera_sdk::{
-    base::{Amount, ApplicationId, ContractAbi, Owner, ServiceAbi, Timestamp},
+    base::{
+        Amount, ApplicationId, BcsHashable, ContractAbi, CryptoHash, Owner, ServiceAbi, Timestamp,
+    },
     graphql::GraphQLMutationRoot,
 };
 use serde::{Deserialize, Serialize};
@@ -10,7 +12,7 @@ pub struct CreditAbi;
 
 impl ContractAbi for CreditAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = CreditResponse;
 }
 
 impl ServiceAbi for CreditAbi {
@@ -39,6 +41,20 @@ impl AgeAmounts {
     }
 }
 
+/// Account an application keeps tokens and credits in on behalf of its users
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApplicationEscrow {
+    pub application_id: ApplicationId,
+}
+
+impl BcsHashable for ApplicationEscrow {}
+
+impl ApplicationEscrow {
+    pub fn owner(application_id: ApplicationId) -> Owner {
+        Owner(CryptoHash::new(&ApplicationEscrow { application_id }))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct InstantiationArgument {
     pub initial_supply: Amount,
@@ -68,6 +84,24 @@ pub enum Operation {
         owner: Owner,
         amount: Amount,
     },
+    Penalize {
+        owner: Owner,
+        amount: Amount,
+    },
+    OutstandingSupply,
+    Spendable {
+        owner: Owner,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub enum CreditResponse {
+    #[default]
+    Ok,
+    /// Credits held by users and not expired yet
+    OutstandingSupply(Amount),
+    /// Credits the owner is able to transfer
+    Spendable(Amount),
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -96,6 +130,10 @@ pub enum Message {
         application_ids: Vec<ApplicationId>,
     },
     RequestSubscribe,
+    Penalize {
+        owner: Owner,
+        amount: Amount,
+    },
 }
 
 /// An error that can occur during the contract execution.
@@ -1,7 +1,7 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashSet};
 
 use async_graphql::SimpleObject;
-use credit::{AgeAmount, AgeAmounts, CreditError, InstantiationArgument};
+use credit::{AgeAmount, AgeAmounts, ApplicationEscrow, CreditError, InstantiationArgument};
 use linera_sdk::{
     base::{Amount, ApplicationId, Owner, Timestamp},
     views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
@@ -43,6 +43,17 @@ impl Credit {
         *self._initial_supply.get()
     }
 
+    /// Expired credits are liquidated back to balance so they are not counted
+    pub(crate) async fn outstanding_supply(&self) -> Amount {
+        self._initial_supply
+            .get()
+            .saturating_sub(*self._balance.get())
+    }
+
+    pub(crate) async fn spendable(&self, owner: Owner) -> Result<Amount, CreditError> {
+        Ok(self.spendables.get(&owner).await?.unwrap_or(Amount::ZERO))
+    }
+
     pub(crate) async fn balance(&self, owner: Option<Owner>) -> Amount {
         match owner {
             Some(owner) => self.balances.get(&owner).await.unwrap().unwrap().sum(),
@@ -112,9 +123,61 @@ impl Credit {
         }
     }
 
+    // Penalized credits return to supply balance, owner may have less than amount to be penalized
+    pub(crate) async fn penalize(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), CreditError> {
+        let spendable = self.spendables.get(&owner).await?.unwrap_or(Amount::ZERO);
+        let amount = amount.min(spendable);
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        self.spendables
+            .insert(&owner, spendable.saturating_sub(amount))?;
+
+        let mut amounts = match self.balances.get(&owner).await? {
+            Some(amounts) => amounts,
+            None => AgeAmounts {
+                amounts: Vec::new(),
+            },
+        };
+        let mut remain = amount;
+        amounts.amounts.retain_mut(|_amount| {
+            if remain == Amount::ZERO {
+                return true;
+            }
+            if _amount.amount.le(&remain) {
+                remain = remain.saturating_sub(_amount.amount);
+                return false;
+            }
+            _amount.amount = _amount.amount.saturating_sub(remain);
+            remain = Amount::ZERO;
+            true
+        });
+        self.balances.insert(&owner, amounts)?;
+
+        self._balance
+            .set(self._balance.get().saturating_add(amount));
+        Ok(())
+    }
+
+    /// Credits held in escrow by applications never expire, they belong to pending deals
     pub(crate) async fn liquidate(&mut self, now: Timestamp) {
+        let escrows = self
+            .transfer_callers
+            .indices()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(ApplicationEscrow::owner)
+            .collect::<HashSet<_>>();
         let owners = self.balances.indices().await.unwrap();
         for owner in owners {
+            if escrows.contains(&owner) {
+                continue;
+            }
             let mut amounts = match self.balances.get(&owner).await {
                 Ok(Some(amounts)) => amounts,
                 _ => continue,
@@ -98,6 +98,16 @@ function create_wallet() {
   linera -w $1 wallet show
 }
 
+function graphql_ids() {
+  printf '\\"%s\\",' "$@" | sed 's/,$//'
+}
+
+function execute_operation() {
+  curl -s -X POST -H "Content-Type: application/json" \
+    -d "{\"query\":\"mutation { $2 }\"}" \
+    http://localhost:9081/chains/$app_deploy_chain/applications/$1 > /dev/null
+}
+
 unset RUSTFLAGS
 unset TMPDIR
 cargo build --release --target wasm32-unknown-unknown
@@ -127,30 +137,37 @@ print $'\U01f499' $LIGHTGREEN " Foundation application deployed"
 echo -e "    Bytecode ID:    $BLUE$foundation_bid$NC"
 echo -e "    Application ID: $BLUE$foundation_appid$NC"
 
+print $'\U01F4AB' $YELLOW " Deploying Notification application ..."
+notification_bid=`linera --with-wallet 1 publish-bytecode ./target/wasm32-unknown-unknown/release/notification_{contract,service}.wasm`
+notification_appid=`linera --with-wallet 1 create-application $notification_bid`
+print $'\U01f499' $LIGHTGREEN " Notification application deployed"
+echo -e "    Bytecode ID:    $BLUE$notification_bid$NC"
+echo -e "    Application ID: $BLUE$notification_appid$NC"
+
 print $'\U01F4AB' $YELLOW " Deploying Feed application ..."
 feed_bid=`linera --with-wallet 1 publish-bytecode ./target/wasm32-unknown-unknown/release/feed_{contract,service}.wasm`
-feed_appid=`linera --with-wallet 1 create-application $feed_bid --json-argument '{"react_interval_ms":60000}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid`
+feed_appid=`linera --with-wallet 1 create-application $feed_bid --json-argument '{"react_interval_ms":60000,"report_threshold":3}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"notification_app_id\":\"$notification_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $notification_appid`
 print $'\U01f499' $LIGHTGREEN " Feed application deployed"
 echo -e "    Bytecode ID:    $BLUE$feed_bid$NC"
 echo -e "    Application ID: $BLUE$feed_appid$NC"
 
 print $'\U01F4AB' $YELLOW " Deploying Market application ..."
 market_bid=`linera --with-wallet 1 publish-bytecode ./target/wasm32-unknown-unknown/release/market_{contract,service}.wasm`
-market_appid=`linera --with-wallet 1 create-application $market_bid --json-argument '{"credits_per_linera":"30","max_credits_percent":30,"trade_fee_percent":3}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid`
+market_appid=`linera --with-wallet 1 create-application $market_bid --json-argument '{"credits_per_linera":"30","max_credits_percent":30,"trade_fee_percent":3,"max_royalty_percent":10,"rate_epoch_ms":86400000,"max_rate_change_percent":10,"rate_smoothing_percent":20}' --json-parameters "{\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"notification_app_id\":\"$notification_appid\"}" --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $notification_appid`
 print $'\U01f499' $LIGHTGREEN " Market application deployed"
 echo -e "    Bytecode ID:    $BLUE$market_bid$NC"
 echo -e "    Application ID: $BLUE$market_appid$NC"
 
 print $'\U01F4AB' $YELLOW " Deploying Review application ..."
 review_bid=`linera --with-wallet 1 publish-bytecode ./target/wasm32-unknown-unknown/release/review_{contract,service}.wasm`
-review_appid=`linera --with-wallet 1 create-application $review_bid --json-argument '{"content_approved_threshold":3,"content_rejected_threshold":2,"asset_approved_threshold":2,"asset_rejected_threshold":2,"reviewer_approved_threshold":2,"reviewer_rejected_threshold":2,"activity_approved_threshold":2,"activity_rejected_threshold":2}' --json-parameters "{\"feed_app_id\":\"$feed_appid\",\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"market_app_id\":\"$market_appid\"}" --required-application-ids $feed_appid --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $market_appid`
+review_appid=`linera --with-wallet 1 create-application $review_bid --json-argument '{"content_quorum":{"kind":"Percent","approved":30,"rejected":20,"minimum":3},"comment_quorum":{"kind":"Absolute","approved":1,"rejected":1,"minimum":1},"asset_quorum":{"kind":"Percent","approved":20,"rejected":20,"minimum":2},"activity_quorum":{"kind":"Absolute","approved":2,"rejected":2,"minimum":1},"reviewer_quorum":{"kind":"Percent","approved":50,"rejected":50,"minimum":2},"report_upheld_threshold":2,"report_dismissed_threshold":2,"reviewer_inactive_days":30,"conflict_of_interest_days":7,"assigned_reviewers":0,"assignment_timeout_ms":86400000,"submission_timeout_ms":604800000,"rubrics":[{"kind":"Content","criteria":["originality","accuracy","formatting"]},{"kind":"Asset","criteria":["originality","formatting"]}],"appeal_quorum":{"kind":"Percent","approved":40,"rejected":40,"minimum":3},"appeal_bond":"100"}' --json-parameters "{\"feed_app_id\":\"$feed_appid\",\"credit_app_id\":\"$credit_appid\",\"foundation_app_id\":\"$foundation_appid\",\"market_app_id\":\"$market_appid\",\"notification_app_id\":\"$notification_appid\"}" --required-application-ids $feed_appid --required-application-ids $credit_appid --required-application-ids $foundation_appid --required-application-ids $market_appid --required-application-ids $notification_appid`
 print $'\U01f499' $LIGHTGREEN " Review application deployed"
 echo -e "    Bytecode ID:    $BLUE$review_bid$NC"
 echo -e "    Application ID: $BLUE$review_appid$NC"
 
 print $'\U01F4AB' $YELLOW " Deploying Activity application ..."
 activity_bid=`linera --with-wallet 1 publish-bytecode ./target/wasm32-unknown-unknown/release/activity_{contract,service}.wasm`
-activity_appid=`linera --with-wallet 1 create-application $activity_bid --json-parameters "{\"review_app_id\":\"$review_appid\",\"foundation_app_id\":\"$foundation_appid\",\"feed_app_id\":\"$feed_appid\"}" --required-application-ids $review_appid --required-application-ids $foundation_appid --required-application-ids $feed_appid`
+activity_appid=`linera --with-wallet 1 create-application $activity_bid --json-parameters "{\"review_app_id\":\"$review_appid\",\"foundation_app_id\":\"$foundation_appid\",\"feed_app_id\":\"$feed_appid\",\"notification_app_id\":\"$notification_appid\"}" --required-application-ids $review_appid --required-application-ids $foundation_appid --required-application-ids $feed_appid --required-application-ids $notification_appid`
 print $'\U01f499' $LIGHTGREEN " Activity application deployed"
 echo -e "    Bytecode ID:    $BLUE$activity_bid$NC"
 echo -e "    Application ID: $BLUE$activity_appid$NC"
@@ -240,6 +257,16 @@ function run_new_service() {
 
 run_new_service 1
 
+until curl -s http://localhost:9081 > /dev/null; do sleep 1; done
+
+print $'\U01F4AB' $YELLOW " Authorizing application callers ..."
+execute_operation $credit_appid "setRewardCallers(applicationIds: [`graphql_ids $feed_appid $review_appid`])"
+execute_operation $credit_appid "setTransferCallers(applicationIds: [`graphql_ids $market_appid $review_appid`])"
+execute_operation $feed_appid "setReportCallers(applicationIds: [`graphql_ids $review_appid`])"
+execute_operation $foundation_appid "setTransferCallers(applicationIds: [`graphql_ids $market_appid`])"
+execute_operation $notification_appid "setNotifyCallers(applicationIds: [`graphql_ids $feed_appid $market_appid $review_appid $activity_appid`])"
+print $'\U01f499' $LIGHTGREEN " Application callers authorized"
+
 trap cleanup INT
 read -p "  Press any key to exit"
 print $'\U01f499' $LIGHTGREEN " Exit ..."
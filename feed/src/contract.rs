@@ -8,13 +8,18 @@ use self::state::Feed;
 use credit::CreditAbi;
 use feed::{
     Content, FeedError, FeedParameters, FeedResponse, InstantiationArgument, Message, Operation,
+    Report, ReportReason,
 };
 use foundation::FoundationAbi;
 use linera_sdk::{
-    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{
+        Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, Timestamp,
+        WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
+use notification::{NotificationAbi, NotificationKind};
 
 pub struct FeedContract {
     state: Feed,
@@ -66,8 +71,10 @@ impl Contract for FeedContract {
                 comment_cid,
                 comment,
                 commentor,
+                score,
             } => self
-                .on_op_comment(cid, comment_cid, comment, commentor)
+                .on_op_comment(cid, comment_cid, comment, commentor, score)
+                .await
                 .expect("Failed OP: comment"),
             Operation::Publish {
                 cid,
@@ -76,13 +83,46 @@ impl Contract for FeedContract {
                 author,
                 cover,
                 abbreviation,
+                score,
             } => self
-                .on_op_publish(cid, title, content, author, cover, abbreviation)
+                .on_op_publish(cid, title, content, author, cover, abbreviation, score)
+                .await
                 .expect("Failed OP: publish"),
             Operation::ContentAuthor { cid } => self
                 .on_op_content_author(cid)
                 .await
                 .expect("Failed OP: content author"),
+            Operation::Report {
+                cid,
+                reason_category,
+                note,
+            } => self
+                .on_op_report(cid, reason_category, note)
+                .await
+                .expect("Failed OP: report"),
+            Operation::ReportedContent { cid } => self
+                .on_op_reported_content(cid)
+                .await
+                .expect("Failed OP: reported content"),
+            Operation::ResolveReport { cid, upheld } => self
+                .on_op_resolve_report(cid, upheld)
+                .await
+                .expect("Failed OP: resolve report"),
+            Operation::SetReportCallers { application_ids } => self
+                .on_op_set_report_callers(application_ids)
+                .expect("Failed OP: set report callers"),
+            Operation::Follow { author } => self.on_op_follow(author).expect("Failed OP: follow"),
+            Operation::Unfollow { author } => {
+                self.on_op_unfollow(author).expect("Failed OP: unfollow")
+            }
+            Operation::Interacted {
+                owner,
+                author,
+                since,
+            } => self
+                .on_op_interacted(owner, author, since)
+                .await
+                .expect("Failed OP: interacted"),
         }
     }
 
@@ -102,8 +142,9 @@ impl Contract for FeedContract {
                 author,
                 cover,
                 abbreviation,
+                score,
             } => self
-                .on_msg_publish(cid, title, content, author, cover, abbreviation)
+                .on_msg_publish(cid, title, content, author, cover, abbreviation, score)
                 .await
                 .expect("Failed MSG: publish"),
             Message::Recommend {
@@ -119,13 +160,38 @@ impl Contract for FeedContract {
                 comment_cid,
                 comment,
                 commentor,
+                score,
             } => self
-                .on_msg_comment(cid, comment_cid, comment, commentor)
+                .on_msg_comment(cid, comment_cid, comment, commentor, score)
                 .await
                 .expect("Failed MSG: comment"),
             Message::RequestSubscribe => self
                 .on_msg_request_subscribe()
                 .expect("Failed MSG: subscribe"),
+            Message::Report {
+                cid,
+                reason_category,
+                note,
+            } => self
+                .on_msg_report(cid, reason_category, note)
+                .await
+                .expect("Failed MSG: report"),
+            Message::ResolveReport { cid, upheld } => self
+                .on_msg_resolve_report(cid, upheld)
+                .await
+                .expect("Failed MSG: resolve report"),
+            Message::SetReportCallers { application_ids } => self
+                .on_msg_set_report_callers(application_ids)
+                .await
+                .expect("Failed MSG: set report callers"),
+            Message::Follow { author } => self
+                .on_msg_follow(author)
+                .await
+                .expect("Failed MSG: follow"),
+            Message::Unfollow { author } => self
+                .on_msg_unfollow(author)
+                .await
+                .expect("Failed MSG: unfollow"),
         }
     }
 
@@ -143,6 +209,10 @@ impl FeedContract {
         self.runtime.application_parameters().foundation_app_id
     }
 
+    fn notification_app_id(&mut self) -> ApplicationId<NotificationAbi> {
+        self.runtime.application_parameters().notification_app_id
+    }
+
     async fn reward_credits(&mut self, owner: Owner, amount: Amount) -> Result<(), FeedError> {
         let call = credit::Operation::Reward { owner, amount };
         let credit_app_id = self.credit_app_id();
@@ -172,6 +242,7 @@ impl FeedContract {
         author: Owner,
         cover: String,
         abbreviation: String,
+        score: u16,
         creation_chain: bool,
     ) -> Result<(), FeedError> {
         match self
@@ -188,6 +259,9 @@ impl FeedContract {
                     likes: 0,
                     dislikes: 0,
                     accounts: HashMap::default(),
+                    reports: 0,
+                    taken_down: false,
+                    score,
                     created_at: self.runtime.system_time(),
                 },
                 author,
@@ -263,6 +337,17 @@ impl FeedContract {
         }
     }
 
+    async fn require_report_caller(&mut self) -> Result<(), FeedError> {
+        let caller_id = match self.runtime.authenticated_caller_id() {
+            Some(caller_id) => caller_id,
+            None => return Err(FeedError::CallerNotAllowed),
+        };
+        if !self.state.is_report_caller(caller_id).await? {
+            return Err(FeedError::CallerNotAllowed);
+        }
+        Ok(())
+    }
+
     fn on_op_like(&mut self, cid: String) -> Result<FeedResponse, FeedError> {
         self.runtime
             .prepare_message(Message::Like { cid })
@@ -312,26 +397,31 @@ impl FeedContract {
         Ok(FeedResponse::Ok)
     }
 
-    fn on_op_comment(
+    async fn on_op_comment(
         &mut self,
         cid: String,
         comment_cid: String,
         comment: String,
         commentor: Owner,
+        score: u16,
     ) -> Result<FeedResponse, FeedError> {
+        if score > 0 {
+            self.require_report_caller().await?;
+        }
         self.runtime
             .prepare_message(Message::Comment {
                 cid,
                 comment_cid,
                 comment,
                 commentor,
+                score,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(FeedResponse::Ok)
     }
 
-    fn on_op_publish(
+    async fn on_op_publish(
         &mut self,
         cid: String,
         title: String,
@@ -339,7 +429,12 @@ impl FeedContract {
         author: Owner,
         cover: String,
         abbreviation: String,
+        score: u16,
     ) -> Result<FeedResponse, FeedError> {
+        // Only the review application knows the rubric score of reviewers
+        if score > 0 {
+            self.require_report_caller().await?;
+        }
         self.runtime
             .prepare_message(Message::Publish {
                 cid,
@@ -348,6 +443,7 @@ impl FeedContract {
                 cover,
                 abbreviation,
                 author,
+                score,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -361,6 +457,106 @@ impl FeedContract {
         }
     }
 
+    // Reports are filed by review on the creation chain, so the caller knows in the same call
+    // whether the content reached the threshold and entered the abuse queue
+    async fn on_op_report(
+        &mut self,
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    ) -> Result<FeedResponse, FeedError> {
+        self.require_report_caller().await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        let reporter = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        self.state
+            .report_content(
+                cid.clone(),
+                Report {
+                    reporter,
+                    reason_category,
+                    note: note.clone(),
+                    created_at: now,
+                },
+                now,
+            )
+            .await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Report {
+                cid: cid.clone(),
+                reason_category,
+                note,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(FeedResponse::ReportedContent(
+            self.state.reported_content(cid).await?,
+        ))
+    }
+
+    async fn on_op_reported_content(&mut self, cid: String) -> Result<FeedResponse, FeedError> {
+        Ok(FeedResponse::ReportedContent(
+            self.state.reported_content(cid).await?,
+        ))
+    }
+
+    fn on_op_follow(&mut self, author: Owner) -> Result<FeedResponse, FeedError> {
+        self.runtime
+            .prepare_message(Message::Follow { author })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
+    fn on_op_unfollow(&mut self, author: Owner) -> Result<FeedResponse, FeedError> {
+        self.runtime
+            .prepare_message(Message::Unfollow { author })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
+    async fn on_op_interacted(
+        &mut self,
+        owner: Owner,
+        author: Owner,
+        since: Timestamp,
+    ) -> Result<FeedResponse, FeedError> {
+        Ok(FeedResponse::Interacted(
+            self.state.interacted(owner, author, since).await?,
+        ))
+    }
+
+    async fn on_op_resolve_report(
+        &mut self,
+        cid: String,
+        upheld: bool,
+    ) -> Result<FeedResponse, FeedError> {
+        self.require_report_caller().await?;
+        self.runtime
+            .prepare_message(Message::ResolveReport { cid, upheld })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
+    fn on_op_set_report_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<FeedResponse, FeedError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetReportCallers { application_ids })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
     async fn on_msg_like(&mut self, cid: String) -> Result<(), FeedError> {
         let signer = self.require_authenticated_signer()?;
         let creation_chain =
@@ -395,6 +591,12 @@ impl FeedContract {
 
     async fn on_msg_tip(&mut self, cid: String, amount: Amount) -> Result<(), FeedError> {
         // TODO: transfer amount from signer to author
+        // Tips are only recorded for conflict of interest checks of reviewers, so a tip without
+        // signer is still relayed as before
+        if let Some(signer) = self.runtime.authenticated_signer() {
+            let now = self.runtime.system_time();
+            self.state.tip(signer, cid.clone(), now).await?;
+        }
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -414,6 +616,7 @@ impl FeedContract {
         author: Owner,
         cover: String,
         abbreviation: String,
+        score: u16,
     ) -> Result<(), FeedError> {
         let creation_chain =
             self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
@@ -425,6 +628,7 @@ impl FeedContract {
             author,
             cover.clone(),
             abbreviation.clone(),
+            score,
             creation_chain,
         )
         .await?;
@@ -440,6 +644,7 @@ impl FeedContract {
                 cover,
                 abbreviation,
                 author,
+                score,
             })
             .with_authentication()
             .send_to(dest);
@@ -463,6 +668,7 @@ impl FeedContract {
             author,
             "".to_string(),
             "".to_string(),
+            0,
             creation_chain,
         )
         .await?;
@@ -490,6 +696,7 @@ impl FeedContract {
         comment_cid: String,
         comment: String,
         commentor: Owner,
+        score: u16,
     ) -> Result<(), FeedError> {
         let creation_chain =
             self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
@@ -501,6 +708,7 @@ impl FeedContract {
             commentor,
             "".to_string(),
             "".to_string(),
+            score,
             creation_chain,
         )
         .await?;
@@ -510,6 +718,18 @@ impl FeedContract {
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        let author = self.state.content_author(cid.clone()).await?;
+        if author != commentor {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                author,
+                NotificationKind::CommentReceived,
+                cid.clone(),
+                format!("New comment {} on your content", comment_cid),
+            );
+        }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
             .prepare_message(Message::Comment {
@@ -517,6 +737,7 @@ impl FeedContract {
                 comment_cid,
                 comment,
                 commentor,
+                score,
             })
             .with_authentication()
             .send_to(dest);
@@ -535,4 +756,234 @@ impl FeedContract {
         );
         Ok(())
     }
+
+    async fn on_msg_report(
+        &mut self,
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    ) -> Result<(), FeedError> {
+        // Reports are only broadcasted from the creation chain
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        let reporter = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        self.state
+            .report_content(
+                cid,
+                Report {
+                    reporter,
+                    reason_category,
+                    note,
+                    created_at: now,
+                },
+                now,
+            )
+            .await
+    }
+
+    async fn on_msg_resolve_report(&mut self, cid: String, upheld: bool) -> Result<(), FeedError> {
+        self.state.resolve_report(cid.clone(), upheld).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ResolveReport { cid, upheld })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_set_report_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), FeedError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        self.state.set_report_callers(application_ids.clone()).await;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetReportCallers { application_ids })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_follow(&mut self, author: Owner) -> Result<(), FeedError> {
+        let signer = self.require_authenticated_signer()?;
+        self.state
+            .follow(signer, author, self.runtime.system_time())
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Follow { author })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_unfollow(&mut self, author: Owner) -> Result<(), FeedError> {
+        let signer = self.require_authenticated_signer()?;
+        self.state.unfollow(signer, author).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Unfollow { author })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use feed::{Content, FeedError, InstantiationArgument, Report, ReportReason};
+    use linera_sdk::{
+        base::{Owner, Timestamp},
+        util::BlockingWait,
+        views::View,
+        ContractRuntime,
+    };
+
+    use super::{state::Feed, FeedContract};
+
+    fn owner(id: u8) -> Owner {
+        format!("{:064x}", id).parse().unwrap()
+    }
+
+    fn create_feed(report_threshold: u16) -> Feed {
+        let runtime = ContractRuntime::<FeedContract>::new();
+        let mut feed = Feed::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        feed.instantiate_feed(InstantiationArgument {
+            react_interval_ms: 0,
+            report_threshold,
+        })
+        .blocking_wait();
+        feed
+    }
+
+    fn create_content(feed: &mut Feed, cid: &str, author: Owner) {
+        feed.create_content(
+            Content {
+                cid: cid.to_string(),
+                comment_to_cid: None,
+                author,
+                title: "Title".to_string(),
+                content: "Content".to_string(),
+                cover: String::new(),
+                abbreviation: String::new(),
+                likes: 0,
+                dislikes: 0,
+                accounts: HashMap::new(),
+                reports: 0,
+                taken_down: false,
+                score: 0,
+                created_at: Timestamp::from(0),
+            },
+            author,
+        )
+        .blocking_wait()
+        .unwrap();
+    }
+
+    fn report(feed: &mut Feed, cid: &str, reporter: Owner, now: u64) -> Result<(), FeedError> {
+        feed.report_content(
+            cid.to_string(),
+            Report {
+                reporter,
+                reason_category: ReportReason::Spam,
+                note: String::new(),
+                created_at: Timestamp::from(now),
+            },
+            Timestamp::from(now),
+        )
+        .blocking_wait()
+    }
+
+    fn queued_at(feed: &Feed, cid: &str) -> Option<Timestamp> {
+        feed.reported_content(cid.to_string())
+            .blocking_wait()
+            .unwrap()
+            .map(|reported| reported.queued_at)
+    }
+
+    #[test]
+    fn report_queue() {
+        let (alice, bob, carol, dave) = (owner(1), owner(2), owner(3), owner(4));
+        let mut feed = create_feed(2);
+        create_content(&mut feed, "content", alice);
+
+        assert!(matches!(
+            report(&mut feed, "missing", bob, 1),
+            Err(FeedError::NotExist)
+        ));
+        assert!(matches!(
+            report(&mut feed, "content", alice, 1),
+            Err(FeedError::InvalidReporter)
+        ));
+        report(&mut feed, "content", bob, 1).unwrap();
+        assert!(matches!(
+            report(&mut feed, "content", bob, 2),
+            Err(FeedError::AlreadyReported)
+        ));
+        assert_eq!(queued_at(&feed, "content"), None);
+
+        // Queued once reports reach the threshold, later reports keep the queue time
+        report(&mut feed, "content", carol, 2).unwrap();
+        assert_eq!(queued_at(&feed, "content"), Some(Timestamp::from(2)));
+        report(&mut feed, "content", dave, 3).unwrap();
+        let reported = feed
+            .reported_content("content".to_string())
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert_eq!(reported.author, alice);
+        assert_eq!(reported.queued_at, Timestamp::from(2));
+        assert_eq!(reported.reports.len(), 3);
+
+        // Dismissed reports are dropped, the content could be reported again
+        feed.resolve_report("content".to_string(), false)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(queued_at(&feed, "content"), None);
+        assert!(matches!(
+            feed.resolve_report("content".to_string(), false)
+                .blocking_wait(),
+            Err(FeedError::InvalidContent)
+        ));
+        report(&mut feed, "content", bob, 4).unwrap();
+        report(&mut feed, "content", carol, 5).unwrap();
+        assert_eq!(queued_at(&feed, "content"), Some(Timestamp::from(5)));
+
+        // Upheld reports take the content down for good
+        feed.resolve_report("content".to_string(), true)
+            .blocking_wait()
+            .unwrap();
+        let content = feed
+            .contents
+            .get(&"content".to_string())
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        assert!(content.taken_down);
+        assert!(matches!(
+            report(&mut feed, "content", dave, 6),
+            Err(FeedError::InvalidContent)
+        ));
+    }
 }
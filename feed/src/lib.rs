@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, Request, Response, SimpleObject};
 use linera_sdk::{
     base::{Amount, ApplicationId, ContractAbi, Owner, ServiceAbi, Timestamp},
     graphql::GraphQLMutationRoot,
@@ -24,6 +24,7 @@ impl ServiceAbi for FeedAbi {
 pub struct FeedParameters {
     pub credit_app_id: ApplicationId<credit::CreditAbi>,
     pub foundation_app_id: ApplicationId<foundation::FoundationAbi>,
+    pub notification_app_id: ApplicationId<notification::NotificationAbi>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -39,12 +40,44 @@ pub struct Content {
     pub likes: u64,
     pub dislikes: u64,
     pub accounts: HashMap<Owner, bool>,
+    pub reports: u64,
+    pub taken_down: bool,
+    /// Aggregated rubric score of reviewers, 100 - 500 for average 1.00 - 5.00, 0 if not scored
+    pub score: u16,
     pub created_at: Timestamp,
 }
 
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum ReportReason {
+    Spam,
+    Abuse,
+    Plagiarism,
+    Misinformation,
+    Illegal,
+    Other,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Report {
+    pub reporter: Owner,
+    pub reason_category: ReportReason,
+    pub note: String,
+    pub created_at: Timestamp,
+}
+
+/// Content which collected enough reports to be judged by reviewers
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ReportedContent {
+    pub cid: String,
+    pub author: Owner,
+    pub reports: Vec<Report>,
+    pub queued_at: Timestamp,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct InstantiationArgument {
     pub react_interval_ms: u64,
+    pub report_threshold: u16,
 }
 
 #[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
@@ -67,6 +100,7 @@ pub enum Operation {
         cover: String,
         abbreviation: String,
         author: Owner,
+        score: u16,
     },
     Recommend {
         cid: String,
@@ -78,10 +112,39 @@ pub enum Operation {
         comment_cid: String,
         comment: String,
         commentor: Owner,
+        score: u16,
     },
     ContentAuthor {
         cid: String,
     },
+    /// Only called by report callers, reports are filed through review
+    Report {
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    },
+    ReportedContent {
+        cid: String,
+    },
+    /// Only called by report callers
+    ResolveReport {
+        cid: String,
+        upheld: bool,
+    },
+    SetReportCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+    Follow {
+        author: Owner,
+    },
+    Unfollow {
+        author: Owner,
+    },
+    Interacted {
+        owner: Owner,
+        author: Owner,
+        since: Timestamp,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -103,6 +166,7 @@ pub enum Message {
         cover: String,
         abbreviation: String,
         author: Owner,
+        score: u16,
     },
     Recommend {
         cid: String,
@@ -114,8 +178,27 @@ pub enum Message {
         comment_cid: String,
         comment: String,
         commentor: Owner,
+        score: u16,
     },
     RequestSubscribe,
+    Report {
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    },
+    ResolveReport {
+        cid: String,
+        upheld: bool,
+    },
+    SetReportCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+    Follow {
+        author: Owner,
+    },
+    Unfollow {
+        author: Owner,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -123,6 +206,8 @@ pub enum FeedResponse {
     #[default]
     Ok,
     ContentAuthor(Option<Owner>),
+    ReportedContent(Option<ReportedContent>),
+    Interacted(bool),
 }
 
 /// An error that can occur during the contract execution.
@@ -157,12 +242,27 @@ pub enum FeedError {
     #[error("Invalid content")]
     InvalidContent,
 
+    #[error("Content already reported")]
+    AlreadyReported,
+
+    #[error("Invalid reporter")]
+    InvalidReporter,
+
+    #[error("Invalid follow")]
+    InvalidFollow,
+
     #[error("Invalid signer")]
     InvalidSigner,
 
     #[error("Invalid message id")]
     InvalidMessageId,
 
+    #[error("Caller not allowed")]
+    CallerNotAllowed,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
+
     #[error("View error")]
     ViewError(#[from] linera_views::views::ViewError),
 }
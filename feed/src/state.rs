@@ -1,12 +1,15 @@
-use async_graphql::SimpleObject;
-use feed::{Content, FeedError, InstantiationArgument};
+use std::collections::HashMap;
+
+use async_graphql::{ComplexObject, SimpleObject};
+use feed::{Content, FeedError, InstantiationArgument, Report, ReportedContent};
 use linera_sdk::{
-    base::{Owner, Timestamp},
-    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+    base::{ApplicationId, Owner, Timestamp},
+    views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
 };
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
+#[graphql(complex)]
 pub struct Feed {
     pub contents: MapView<String, Content>,
     pub content_recommends: MapView<String, Vec<String>>,
@@ -16,12 +19,48 @@ pub struct Feed {
     pub react_accounts: MapView<Owner, Timestamp>,
     pub collection_recommends: MapView<u64, Vec<String>>,
     pub collection_comments: MapView<u64, Vec<String>>,
+    pub report_threshold: RegisterView<u16>,
+    pub content_reports: MapView<String, Vec<Report>>,
+    /// Contents waiting for reviewers to uphold or dismiss the reports
+    pub report_queue: MapView<String, Timestamp>,
+    /// Latest tip time from an account to authors
+    pub tips: MapView<Owner, HashMap<Owner, Timestamp>>,
+    /// Authors followed by an account with follow time
+    pub follows: MapView<Owner, HashMap<Owner, Timestamp>>,
+    /// Applications allowed to file and resolve reports and to carry review scores
+    pub report_callers: SetView<ApplicationId>,
+}
+
+#[ComplexObject]
+impl Feed {
+    /// Published contents ranked by reviewer score, then by likes
+    async fn contents_by_score(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<Content>> {
+        let mut contents = Vec::new();
+        for cid in self.contents.indices().await? {
+            if let Some(content) = self.contents.get(&cid).await? {
+                if content.comment_to_cid.is_none() && !content.taken_down {
+                    contents.push(content);
+                }
+            }
+        }
+        contents.sort_by(|a, b| b.score.cmp(&a.score).then(b.likes.cmp(&a.likes)));
+        Ok(contents
+            .into_iter()
+            .skip(offset.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
 }
 
 #[allow(dead_code)]
 impl Feed {
     pub(crate) async fn instantiate_feed(&mut self, argument: InstantiationArgument) {
         self.react_interval_ms.set(argument.react_interval_ms);
+        self.report_threshold.set(argument.report_threshold);
     }
 
     pub(crate) async fn create_content(
@@ -141,4 +180,162 @@ impl Feed {
             Err(err) => Err(FeedError::ViewError(err)),
         }
     }
+
+    pub(crate) async fn report_content(
+        &mut self,
+        cid: String,
+        report: Report,
+        now: Timestamp,
+    ) -> Result<(), FeedError> {
+        let mut content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            None => return Err(FeedError::NotExist),
+        };
+        if content.taken_down {
+            return Err(FeedError::InvalidContent);
+        }
+        if content.author == report.reporter {
+            return Err(FeedError::InvalidReporter);
+        }
+        let mut reports = self.content_reports.get(&cid).await?.unwrap_or_default();
+        if reports
+            .iter()
+            .any(|_report| _report.reporter == report.reporter)
+        {
+            return Err(FeedError::AlreadyReported);
+        }
+        reports.push(report);
+        content.reports += 1;
+        self.content_reports.insert(&cid, reports)?;
+        self.contents.insert(&cid, content.clone())?;
+
+        let threshold = (*self.report_threshold.get()).max(1) as u64;
+        if content.reports >= threshold && !self.report_queue.contains_key(&cid).await? {
+            self.report_queue.insert(&cid, now)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn reported_content(
+        &self,
+        cid: String,
+    ) -> Result<Option<ReportedContent>, FeedError> {
+        let queued_at = match self.report_queue.get(&cid).await? {
+            Some(queued_at) => queued_at,
+            None => return Ok(None),
+        };
+        Ok(Some(ReportedContent {
+            cid: cid.clone(),
+            author: self.content_author(cid.clone()).await?,
+            reports: self.content_reports.get(&cid).await?.unwrap_or_default(),
+            queued_at,
+        }))
+    }
+
+    pub(crate) async fn resolve_report(
+        &mut self,
+        cid: String,
+        upheld: bool,
+    ) -> Result<(), FeedError> {
+        if !self.report_queue.contains_key(&cid).await? {
+            return Err(FeedError::InvalidContent);
+        }
+        self.report_queue.remove(&cid)?;
+        let mut content = match self.contents.get(&cid).await? {
+            Some(content) => content,
+            None => return Err(FeedError::NotExist),
+        };
+        if upheld {
+            content.taken_down = true;
+        } else {
+            // Dismissed reports are dropped so the content could be reported again
+            content.reports = 0;
+            self.content_reports.remove(&cid)?;
+        }
+        self.contents.insert(&cid, content)?;
+        Ok(())
+    }
+
+    pub(crate) async fn set_report_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.report_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn is_report_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, FeedError> {
+        Ok(self.report_callers.contains(&application_id).await?)
+    }
+
+    // Tips of unknown contents are not recorded
+    pub(crate) async fn tip(
+        &mut self,
+        owner: Owner,
+        cid: String,
+        now: Timestamp,
+    ) -> Result<(), FeedError> {
+        let author = match self.contents.get(&cid).await? {
+            Some(content) => content.author,
+            None => return Ok(()),
+        };
+        let mut tips = self.tips.get(&owner).await?.unwrap_or_default();
+        tips.insert(author, now);
+        self.tips.insert(&owner, tips)?;
+        Ok(())
+    }
+
+    pub(crate) async fn follow(
+        &mut self,
+        owner: Owner,
+        author: Owner,
+        now: Timestamp,
+    ) -> Result<(), FeedError> {
+        if owner == author {
+            return Err(FeedError::InvalidFollow);
+        }
+        let mut follows = self.follows.get(&owner).await?.unwrap_or_default();
+        if follows.contains_key(&author) {
+            return Err(FeedError::InvalidFollow);
+        }
+        follows.insert(author, now);
+        self.follows.insert(&owner, follows)?;
+        Ok(())
+    }
+
+    pub(crate) async fn unfollow(&mut self, owner: Owner, author: Owner) -> Result<(), FeedError> {
+        let mut follows = self.follows.get(&owner).await?.unwrap_or_default();
+        if follows.remove(&author).is_none() {
+            return Err(FeedError::InvalidFollow);
+        }
+        self.follows.insert(&owner, follows)?;
+        Ok(())
+    }
+
+    // Owner tipped or followed author after since
+    pub(crate) async fn interacted(
+        &self,
+        owner: Owner,
+        author: Owner,
+        since: Timestamp,
+    ) -> Result<bool, FeedError> {
+        if let Some(tips) = self.tips.get(&owner).await? {
+            if tips
+                .get(&author)
+                .map_or(false, |tipped_at| *tipped_at >= since)
+            {
+                return Ok(true);
+            }
+        }
+        if let Some(follows) = self.follows.get(&owner).await? {
+            if follows
+                .get(&author)
+                .map_or(false, |followed_at| *followed_at >= since)
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
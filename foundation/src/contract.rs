@@ -5,11 +5,12 @@ mod state;
 use std::collections::HashSet;
 
 use self::state::Foundation;
+use credit::ApplicationEscrow;
 use foundation::{
     FoundationError, FoundationResponse, InstantiationArgument, Message, Operation, RewardType,
 };
 use linera_sdk::{
-    base::{Amount, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
@@ -73,6 +74,10 @@ impl Contract for FoundationContract {
             Operation::Balance { owner } => {
                 self.on_op_balance(owner).await.expect("Failed OP: balance")
             }
+            Operation::FoundationBalance => self
+                .on_op_foundation_balance()
+                .await
+                .expect("Failed OP: foundation balance"),
             Operation::Deposit { from, amount } => self
                 .on_op_deposit(from, amount)
                 .expect("Failed OP: deposit"),
@@ -91,7 +96,11 @@ impl Contract for FoundationContract {
                 .expect("Failed OP: reward"),
             Operation::Transfer { from, to, amount } => self
                 .on_op_transfer(from, to, amount)
+                .await
                 .expect("Failed OP: transfer"),
+            Operation::SetTransferCallers { application_ids } => self
+                .on_op_set_transfer_callers(application_ids)
+                .expect("Failed OP: set transfer callers"),
         }
     }
 
@@ -147,6 +156,10 @@ impl Contract for FoundationContract {
                 )
                 .await
                 .expect("Failed MSG: activity rewards"),
+            Message::SetTransferCallers { application_ids } => self
+                .on_msg_set_transfer_callers(application_ids)
+                .await
+                .expect("Failed MSG: set transfer callers"),
         }
     }
 
@@ -233,6 +246,12 @@ impl FoundationContract {
         Ok(FoundationResponse::Ok)
     }
 
+    async fn on_op_foundation_balance(&mut self) -> Result<FoundationResponse, FoundationError> {
+        Ok(FoundationResponse::Balance(
+            *self.state.foundation_balance.get(),
+        ))
+    }
+
     async fn on_op_balance(&mut self, owner: Owner) -> Result<FoundationResponse, FoundationError> {
         Ok(FoundationResponse::Balance(
             self.state.balance(owner).await?,
@@ -283,12 +302,57 @@ impl FoundationContract {
         Ok(FoundationResponse::Ok)
     }
 
-    fn on_op_transfer(
+    fn on_op_set_transfer_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<FoundationResponse, FoundationError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(FoundationError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetTransferCallers { application_ids })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FoundationResponse::Ok)
+    }
+
+    /// Whitelisted application calling on the creation chain, if any
+    async fn transfer_caller(&mut self) -> Result<Option<ApplicationId>, FoundationError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(None);
+        }
+        let Some(caller) = self.runtime.authenticated_caller_id() else {
+            return Ok(None);
+        };
+        if !self.state.transfer_callers.contains(&caller).await? {
+            return Ok(None);
+        }
+        Ok(Some(caller))
+    }
+
+    // Whitelisted applications on the creation chain get the transfer applied in the same
+    // transaction, so the caller fails together with the transfer instead of trusting a
+    // message sent later. They only move their own escrow or the signer's tokens
+    async fn on_op_transfer(
         &mut self,
         from: Owner,
         to: Owner,
         amount: Amount,
     ) -> Result<FoundationResponse, FoundationError> {
+        if let Some(caller) = self.transfer_caller().await? {
+            if from != ApplicationEscrow::owner(caller)
+                && Some(from) != self.runtime.authenticated_signer()
+            {
+                return Err(FoundationError::CallerNotAllowed);
+            }
+            self.state.transfer(from, to, amount).await?;
+            let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+            self.runtime
+                .prepare_message(Message::Transfer { from, to, amount })
+                .with_authentication()
+                .send_to(dest);
+            return Ok(FoundationResponse::Ok);
+        }
         self.runtime
             .prepare_message(Message::Transfer { from, to, amount })
             .with_authentication()
@@ -403,6 +467,12 @@ impl FoundationContract {
         to: Owner,
         amount: Amount,
     ) -> Result<(), FoundationError> {
+        // Users only move their own tokens, subscribers replay what the creation chain applied
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id
+            && Some(from) != self.runtime.authenticated_signer()
+        {
+            return Err(FoundationError::InvalidSigner);
+        }
         self.state.transfer(from, to, amount).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
@@ -444,4 +514,25 @@ impl FoundationContract {
             .send_to(dest);
         Ok(())
     }
+
+    async fn on_msg_set_transfer_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), FoundationError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FoundationError::OperationNotAllowed);
+        }
+        self.state
+            .set_transfer_callers(application_ids.clone())
+            .await;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetTransferCallers { application_ids })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
 }
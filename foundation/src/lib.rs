@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use async_graphql::{Enum, Request, Response};
 use linera_sdk::{
-    base::{Amount, ArithmeticError, ContractAbi, Owner, ServiceAbi},
+    base::{Amount, ApplicationId, ArithmeticError, ContractAbi, Owner, ServiceAbi},
     graphql::GraphQLMutationRoot,
 };
 use serde::{Deserialize, Serialize};
@@ -79,6 +79,10 @@ pub enum Operation {
     Balance {
         owner: Owner,
     },
+    FoundationBalance,
+    SetTransferCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,6 +123,9 @@ pub enum Message {
         activity_id: u64,
         amount: Amount,
     },
+    SetTransferCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
 
 /// An error that can occur during the contract execution.
@@ -164,4 +171,10 @@ pub enum FoundationError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Caller not allowed")]
+    CallerNotAllowed,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
 }
@@ -3,8 +3,8 @@ use std::collections::HashSet;
 use async_graphql::SimpleObject;
 use foundation::{FoundationError, InstantiationArgument, RewardType};
 use linera_sdk::{
-    base::{Amount, Owner},
-    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+    base::{Amount, ApplicationId, Owner},
+    views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
 };
 
 #[derive(RootView, SimpleObject)]
@@ -21,6 +21,8 @@ pub struct Foundation {
     pub activity_reward_balance: RegisterView<Amount>,
     pub activity_lock_funds: MapView<u64, Amount>,
     pub user_balances: MapView<Owner, Amount>,
+    /// Applications whose transfers are applied synchronously on the creation chain
+    pub transfer_callers: SetView<ApplicationId>,
 }
 
 #[allow(dead_code)]
@@ -43,6 +45,12 @@ impl Foundation {
         Ok(())
     }
 
+    pub(crate) async fn set_transfer_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.transfer_callers.insert(application_id).unwrap())
+    }
+
     pub(crate) async fn initialization_argument(
         &self,
     ) -> Result<InstantiationArgument, FoundationError> {
@@ -3,14 +3,21 @@
 mod state;
 
 use self::state::Market;
-use credit::CreditAbi;
-use foundation::FoundationAbi;
+use credit::{ApplicationEscrow, CreditAbi, CreditResponse};
+use foundation::{FoundationAbi, FoundationResponse};
 use linera_sdk::{
-    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{
+        Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, Timestamp,
+        WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use market::{InstantiationArgument, MarketError, MarketParameters, Message, Operation};
+use market::{
+    AuctionKind, CollectionMetadata, FeeSchedule, InstantiationArgument, MarketError,
+    MarketParameters, Message, NFTAttribute, NFTKey, Offer, Operation, Sale, SaleFees, SaleKind,
+};
+use notification::{NotificationAbi, NotificationKind};
 
 const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
 
@@ -40,9 +47,14 @@ impl Contract for MarketContract {
     async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
         self.runtime.application_parameters();
         self.state.instantiate_market(argument).await;
+        self.state.admin.set(self.runtime.authenticated_signer());
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.state
+            .migrate()
+            .await
+            .expect("Failed OP: migrate state");
         let _ = self
             .require_authenticated_signer()
             .expect("Failed OP: check authenticated signer");
@@ -52,19 +64,25 @@ impl Contract for MarketContract {
                 uri_index,
                 price,
                 name,
+                attributes,
+                editions,
             } => self
-                .on_op_mint_nft(collection_id, uri_index, price, name)
+                .on_op_mint_nft(collection_id, uri_index, price, name, attributes, editions)
                 .expect("Failed OP: mint nft"),
             Operation::BuyNFT {
                 collection_id,
                 token_id,
                 credits,
+                referrer,
             } => self
-                .on_op_buy_nft(collection_id, token_id, credits)
+                .on_op_buy_nft(collection_id, token_id, credits, referrer)
                 .expect("Failed OP: buy nft"),
             Operation::UpdateCreditsPerLinera { credits_per_linera } => self
                 .on_op_update_credits_per_linera(credits_per_linera)
                 .expect("Failed OP: update credits per linera"),
+            Operation::RefreshCreditsPerLinera => self
+                .on_op_refresh_credits_per_linera()
+                .expect("Failed OP: refresh credits per linera"),
             Operation::UpdateNFTPrice {
                 collection_id,
                 token_id,
@@ -99,13 +117,141 @@ impl Contract for MarketContract {
                 name,
                 uris,
                 publisher,
+                royalty_percent,
+                metadata,
             } => self
-                .on_op_create_collection(base_uri, price, name, uris, publisher)
+                .on_op_create_collection(
+                    base_uri,
+                    price,
+                    name,
+                    uris,
+                    publisher,
+                    royalty_percent,
+                    metadata,
+                )
                 .expect("Failed OP: create collection"),
+            Operation::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            } => self
+                .on_op_transfer_nft(collection_id, token_id, to)
+                .expect("Failed OP: transfer nft"),
+            Operation::BatchTransfer { nfts, to } => self
+                .on_op_batch_transfer(nfts, to)
+                .expect("Failed OP: batch transfer"),
+            Operation::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+            } => self
+                .on_op_create_auction(
+                    collection_id,
+                    token_id,
+                    kind,
+                    reserve,
+                    start_price,
+                    start_at,
+                    end_at,
+                )
+                .expect("Failed OP: create auction"),
+            Operation::Bid {
+                auction_id,
+                amount,
+                credits,
+            } => self
+                .on_op_bid(auction_id, amount, credits)
+                .expect("Failed OP: bid"),
+            Operation::SettleAuction { auction_id } => self
+                .on_op_settle_auction(auction_id)
+                .expect("Failed OP: settle auction"),
+            Operation::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                credits,
+                expires_at,
+            } => self
+                .on_op_make_offer(collection_id, token_id, amount, credits, expires_at)
+                .expect("Failed OP: make offer"),
+            Operation::CancelOffer { offer_id } => self
+                .on_op_cancel_offer(offer_id)
+                .expect("Failed OP: cancel offer"),
+            Operation::AcceptOffer { offer_id } => self
+                .on_op_accept_offer(offer_id)
+                .expect("Failed OP: accept offer"),
+            Operation::CounterOffer { offer_id, price } => self
+                .on_op_counter_offer(offer_id, price)
+                .expect("Failed OP: counter offer"),
+            Operation::AcceptCounter { offer_id } => self
+                .on_op_accept_counter(offer_id)
+                .expect("Failed OP: accept counter"),
+            Operation::PublicMint { collection_id } => self
+                .on_op_public_mint(collection_id)
+                .expect("Failed OP: public mint"),
+            Operation::CreateBundle { nfts, price } => self
+                .on_op_create_bundle(nfts, price)
+                .expect("Failed OP: create bundle"),
+            Operation::BuyBundle {
+                bundle_id,
+                credits,
+                referrer,
+            } => self
+                .on_op_buy_bundle(bundle_id, credits, referrer)
+                .expect("Failed OP: buy bundle"),
+            Operation::CancelBundle { bundle_id } => self
+                .on_op_cancel_bundle(bundle_id)
+                .expect("Failed OP: cancel bundle"),
+            Operation::ListEditions {
+                collection_id,
+                token_id,
+                units,
+                price,
+            } => self
+                .on_op_list_editions(collection_id, token_id, units, price)
+                .expect("Failed OP: list editions"),
+            Operation::BuyEditions {
+                collection_id,
+                token_id,
+                seller,
+                units,
+                credits,
+                referrer,
+            } => self
+                .on_op_buy_editions(collection_id, token_id, seller, units, credits, referrer)
+                .expect("Failed OP: buy editions"),
+            Operation::TransferEditions {
+                collection_id,
+                token_id,
+                to,
+                units,
+            } => self
+                .on_op_transfer_editions(collection_id, token_id, to, units)
+                .expect("Failed OP: transfer editions"),
+            Operation::Deposit { amount } => {
+                self.on_op_deposit(amount).expect("Failed OP: deposit")
+            }
+            Operation::Withdraw { amount } => {
+                self.on_op_withdraw(amount).expect("Failed OP: withdraw")
+            }
+            Operation::UpdateFeeSchedule { schedule } => self
+                .on_op_update_fee_schedule(schedule)
+                .expect("Failed OP: update fee schedule"),
+            Operation::SetAdmin { admin } => {
+                self.on_op_set_admin(admin).expect("Failed OP: set admin")
+            }
         }
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
+        self.state
+            .migrate()
+            .await
+            .expect("Failed MSG: migrate state");
         match message {
             Message::InstantiationArgument { argument } => self
                 .on_msg_instantiation_argument(argument)
@@ -117,8 +263,20 @@ impl Contract for MarketContract {
                 name,
                 uris,
                 publisher,
+                royalty_percent,
+                metadata,
+                created_at,
             } => self
-                .on_msg_create_collection(base_uri, price, name, uris, publisher)
+                .on_msg_create_collection(
+                    base_uri,
+                    price,
+                    name,
+                    uris,
+                    publisher,
+                    royalty_percent,
+                    metadata,
+                    created_at,
+                )
                 .await
                 .expect("Failed MSG: create collection"),
             Message::MintNFT {
@@ -126,22 +284,45 @@ impl Contract for MarketContract {
                 uri_index,
                 price,
                 name,
+                attributes,
+                editions,
+                minted_at,
             } => self
-                .on_msg_mint_nft(collection_id, uri_index, price, name)
+                .on_msg_mint_nft(
+                    collection_id,
+                    uri_index,
+                    price,
+                    name,
+                    attributes,
+                    editions,
+                    minted_at,
+                )
                 .await
                 .expect("Failed MSG: mint nft"),
             Message::BuyNFT {
                 collection_id,
                 token_id,
                 credits,
+                referrer,
+                bought_at,
             } => self
-                .on_msg_buy_nft(collection_id, token_id, credits)
+                .on_msg_buy_nft(collection_id, token_id, credits, referrer, bought_at)
                 .await
                 .expect("Failed MSG: buy NFT"),
-            Message::UpdateCreditsPerLinera { credits_per_linera } => self
-                .on_msg_update_credits_per_linera(credits_per_linera)
+            Message::UpdateCreditsPerLinera {
+                credits_per_linera,
+                updated_at,
+            } => self
+                .on_msg_update_credits_per_linera(credits_per_linera, updated_at)
                 .await
                 .expect("Failed MSG: update credits per linera"),
+            Message::RefreshCreditsPerLinera {
+                target,
+                refreshed_at,
+            } => self
+                .on_msg_refresh_credits_per_linera(target, refreshed_at)
+                .await
+                .expect("Failed MSG: refresh credits per linera"),
             Message::UpdateNFTPrice {
                 collection_id,
                 token_id,
@@ -173,7 +354,183 @@ impl Contract for MarketContract {
                 .expect("Failed MSG: set avatar"),
             Message::RequestSubscribe => self
                 .on_msg_request_subscribe()
+                .await
                 .expect("Failed MSG: subscribe"),
+            Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            } => self
+                .on_msg_transfer_nft(collection_id, token_id, to)
+                .await
+                .expect("Failed MSG: transfer nft"),
+            Message::BatchTransfer { nfts, to } => self
+                .on_msg_batch_transfer(nfts, to)
+                .await
+                .expect("Failed MSG: batch transfer"),
+            Message::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+                created_at,
+            } => self
+                .on_msg_create_auction(
+                    collection_id,
+                    token_id,
+                    kind,
+                    reserve,
+                    start_price,
+                    start_at,
+                    end_at,
+                    created_at,
+                )
+                .await
+                .expect("Failed MSG: create auction"),
+            Message::Bid {
+                auction_id,
+                amount,
+                credits,
+                bid_at,
+            } => self
+                .on_msg_bid(auction_id, amount, credits, bid_at)
+                .await
+                .expect("Failed MSG: bid"),
+            Message::SettleAuction {
+                auction_id,
+                settled_at,
+            } => self
+                .on_msg_settle_auction(auction_id, settled_at)
+                .await
+                .expect("Failed MSG: settle auction"),
+            Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                credits,
+                expires_at,
+                created_at,
+            } => self
+                .on_msg_make_offer(
+                    collection_id,
+                    token_id,
+                    amount,
+                    credits,
+                    expires_at,
+                    created_at,
+                )
+                .await
+                .expect("Failed MSG: make offer"),
+            Message::CancelOffer { offer_id } => self
+                .on_msg_cancel_offer(offer_id)
+                .await
+                .expect("Failed MSG: cancel offer"),
+            Message::AcceptOffer {
+                offer_id,
+                accepted_at,
+            } => self
+                .on_msg_accept_offer(offer_id, accepted_at)
+                .await
+                .expect("Failed MSG: accept offer"),
+            Message::CounterOffer {
+                offer_id,
+                price,
+                countered_at,
+            } => self
+                .on_msg_counter_offer(offer_id, price, countered_at)
+                .await
+                .expect("Failed MSG: counter offer"),
+            Message::AcceptCounter {
+                offer_id,
+                accepted_at,
+            } => self
+                .on_msg_accept_counter(offer_id, accepted_at)
+                .await
+                .expect("Failed MSG: accept counter"),
+            Message::PublicMint {
+                collection_id,
+                minted_at,
+            } => self
+                .on_msg_public_mint(collection_id, minted_at)
+                .await
+                .expect("Failed MSG: public mint"),
+            Message::CreateBundle {
+                nfts,
+                price,
+                created_at,
+            } => self
+                .on_msg_create_bundle(nfts, price, created_at)
+                .await
+                .expect("Failed MSG: create bundle"),
+            Message::BuyBundle {
+                bundle_id,
+                credits,
+                referrer,
+                bought_at,
+            } => self
+                .on_msg_buy_bundle(bundle_id, credits, referrer, bought_at)
+                .await
+                .expect("Failed MSG: buy bundle"),
+            Message::CancelBundle { bundle_id } => self
+                .on_msg_cancel_bundle(bundle_id)
+                .await
+                .expect("Failed MSG: cancel bundle"),
+            Message::ListEditions {
+                collection_id,
+                token_id,
+                units,
+                price,
+            } => self
+                .on_msg_list_editions(collection_id, token_id, units, price)
+                .await
+                .expect("Failed MSG: list editions"),
+            Message::BuyEditions {
+                collection_id,
+                token_id,
+                seller,
+                units,
+                credits,
+                referrer,
+                bought_at,
+            } => self
+                .on_msg_buy_editions(
+                    collection_id,
+                    token_id,
+                    seller,
+                    units,
+                    credits,
+                    referrer,
+                    bought_at,
+                )
+                .await
+                .expect("Failed MSG: buy editions"),
+            Message::TransferEditions {
+                collection_id,
+                token_id,
+                to,
+                units,
+            } => self
+                .on_msg_transfer_editions(collection_id, token_id, to, units)
+                .await
+                .expect("Failed MSG: transfer editions"),
+            Message::Deposit { amount } => self
+                .on_msg_deposit(amount)
+                .await
+                .expect("Failed MSG: deposit"),
+            Message::Withdraw { amount } => self
+                .on_msg_withdraw(amount)
+                .await
+                .expect("Failed MSG: withdraw"),
+            Message::UpdateFeeSchedule { schedule } => self
+                .on_msg_update_fee_schedule(schedule)
+                .await
+                .expect("Failed MSG: update fee schedule"),
+            Message::SetAdmin { admin } => {
+                self.on_msg_set_admin(admin).expect("Failed MSG: set admin")
+            }
         }
     }
 
@@ -191,6 +548,10 @@ impl MarketContract {
         self.runtime.application_parameters().foundation_app_id
     }
 
+    fn notification_app_id(&mut self) -> ApplicationId<NotificationAbi> {
+        self.runtime.application_parameters().notification_app_id
+    }
+
     async fn transfer_credits(
         &mut self,
         from: Owner,
@@ -226,6 +587,211 @@ impl MarketContract {
         Ok(())
     }
 
+    fn escrow_owner(&mut self) -> Owner {
+        ApplicationEscrow::owner(self.runtime.application_id().forget_abi())
+    }
+
+    /// Creation chain decides the time, subscribers replay the one it broadcast
+    fn message_time(&mut self, timestamp: Timestamp) -> Timestamp {
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            return self.runtime.system_time();
+        }
+        timestamp
+    }
+
+    async fn escrow(
+        &mut self,
+        from: Owner,
+        amount: Amount,
+        credits: Amount,
+    ) -> Result<(), MarketError> {
+        let escrow = self.escrow_owner();
+        if amount > Amount::ZERO {
+            self.transfer_tokens(from, escrow, amount).await?;
+        }
+        if credits > Amount::ZERO {
+            self.transfer_credits(from, escrow, credits).await?;
+        }
+        Ok(())
+    }
+
+    async fn refund_escrow(
+        &mut self,
+        to: Owner,
+        amount: Amount,
+        credits: Amount,
+    ) -> Result<(), MarketError> {
+        let escrow = self.escrow_owner();
+        if amount > Amount::ZERO {
+            self.transfer_tokens(escrow, to, amount).await?;
+        }
+        if credits > Amount::ZERO {
+            self.transfer_credits(escrow, to, credits).await?;
+        }
+        Ok(())
+    }
+
+    /// Royalty is recorded on every chain but only paid on the creation chain
+    async fn charge_royalty(
+        &mut self,
+        collection_id: u64,
+        seller: Owner,
+        amount: Amount,
+    ) -> Result<Option<(Owner, Amount)>, MarketError> {
+        let royalty = self.state.royalty(collection_id, seller, amount).await?;
+        if let Some((publisher, royalty)) = royalty {
+            self.state.record_royalty(publisher, royalty).await?;
+        }
+        Ok(royalty)
+    }
+
+    /// Split fees by the schedule. Shares without a recipient are deposited to foundation
+    async fn pay_fees(
+        &mut self,
+        from: Owner,
+        fees: SaleFees,
+        referrer: Option<Owner>,
+    ) -> Result<(), MarketError> {
+        let mut commission = fees.foundation;
+        match self.state.fee_schedule.get().marketplace_recipient {
+            Some(recipient) if fees.marketplace > Amount::ZERO => {
+                self.transfer_tokens(from, recipient, fees.marketplace)
+                    .await?
+            }
+            _ => commission = commission.saturating_add(fees.marketplace),
+        }
+        match referrer {
+            Some(referrer) if fees.referral > Amount::ZERO => {
+                self.transfer_tokens(from, referrer, fees.referral).await?
+            }
+            _ => commission = commission.saturating_add(fees.referral),
+        }
+        if commission > Amount::ZERO {
+            self.deposit_commission(from, commission).await?;
+        }
+        Ok(())
+    }
+
+    /// Pay escrowed funds to the seller with trading fee split by the fee schedule
+    /// and royalty paid to the publisher. Fees and royalty are charged on the full value
+    /// like a purchase from deposit, but paid in the escrowed tokens only, so callers cap
+    /// fees at the escrowed amount
+    async fn pay_from_escrow(
+        &mut self,
+        seller: Owner,
+        amount: Amount,
+        credits: Amount,
+        fees: SaleFees,
+        royalty: Option<(Owner, Amount)>,
+    ) -> Result<(), MarketError> {
+        let escrow = self.escrow_owner();
+        let mut proceeds = amount.saturating_sub(fees.total());
+        if let Some((publisher, royalty)) = royalty {
+            let royalty = royalty.min(proceeds);
+            if royalty > Amount::ZERO {
+                self.transfer_tokens(escrow, publisher, royalty).await?;
+            }
+            proceeds = proceeds.saturating_sub(royalty);
+        }
+        if proceeds > Amount::ZERO {
+            self.transfer_tokens(escrow, seller, proceeds).await?;
+        }
+        self.pay_fees(escrow, fees, None).await?;
+        if credits > Amount::ZERO {
+            self.transfer_credits(escrow, seller, credits).await?;
+        }
+        Ok(())
+    }
+
+    /// Pay a purchase out of the buyer's deposit held by the escrow. The deposit is
+    /// debited on every chain so all of them accept or reject the purchase alike
+    #[allow(clippy::too_many_arguments)]
+    async fn pay_from_deposit(
+        &mut self,
+        buyer: Owner,
+        seller: Owner,
+        price: Amount,
+        credits: Amount,
+        fees: SaleFees,
+        royalties: Vec<(Owner, Amount)>,
+        referrer: Option<Owner>,
+    ) -> Result<(), MarketError> {
+        if referrer == Some(buyer) {
+            return Err(MarketError::InvalidReferrer);
+        }
+        let royalty = royalties.iter().fold(Amount::ZERO, |sum, (_, royalty)| {
+            sum.saturating_add(*royalty)
+        });
+        let quote = self.state.quote_sale(price, credits, fees, royalty).await?;
+        self.state.spend_balance(buyer, quote.payment).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let escrow = self.escrow_owner();
+        for (publisher, royalty) in royalties {
+            if royalty > Amount::ZERO {
+                self.transfer_tokens(escrow, publisher, royalty).await?;
+            }
+        }
+        if quote.proceeds > Amount::ZERO {
+            self.transfer_tokens(escrow, seller, quote.proceeds).await?;
+        }
+        self.pay_fees(escrow, fees, referrer).await?;
+        if credits > Amount::ZERO {
+            self.transfer_credits(buyer, seller, credits).await?;
+        }
+        Ok(())
+    }
+
+    async fn settle_auction(&mut self, auction_id: u64, now: Timestamp) -> Result<(), MarketError> {
+        let auction = self.state.settle_auction(auction_id, now).await?;
+        let Some(bid) = auction.bids.last() else {
+            return Ok(());
+        };
+        let royalty = self
+            .charge_royalty(auction.collection_id, auction.seller, bid.value)
+            .await?;
+        // The schedule may have changed since the bid, fees never exceed what is escrowed
+        let fees = self
+            .state
+            .trading_fee(auction.collection_id, bid.value)
+            .await?
+            .capped(bid.amount);
+        self.state
+            .record_sale(Sale {
+                sale_id: 0,
+                kind: SaleKind::Auction,
+                collection_id: auction.collection_id,
+                token_id: auction.token_id,
+                seller: auction.seller,
+                buyer: bid.bidder,
+                price: bid.value,
+                credits: bid.credits,
+                fee: fees.total(),
+                royalty: royalty.map_or(Amount::ZERO, |(_, royalty)| royalty),
+                created_at: now,
+            })
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.pay_from_escrow(auction.seller, bid.amount, bid.credits, fees, royalty)
+            .await?;
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            auction.seller,
+            NotificationKind::NFTSold,
+            format!("{}:{}", auction.collection_id, auction.token_id),
+            format!(
+                "Your NFT is sold in auction {} at {} with {} credits",
+                auction_id, bid.amount, bid.credits
+            ),
+        );
+        Ok(())
+    }
+
     fn require_message_id(&mut self) -> Result<MessageId, MarketError> {
         match self.runtime.message_id() {
             Some(message_id) => Ok(message_id),
@@ -246,13 +812,19 @@ impl MarketContract {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        attributes: Option<Vec<NFTAttribute>>,
+        editions: Option<u16>,
     ) -> Result<(), MarketError> {
+        let minted_at = self.runtime.system_time();
         self.runtime
             .prepare_message(Message::MintNFT {
                 collection_id,
                 uri_index,
                 price,
                 name,
+                attributes: attributes.unwrap_or_default(),
+                editions: editions.unwrap_or(1),
+                minted_at,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -264,12 +836,16 @@ impl MarketContract {
         collection_id: u64,
         token_id: u16,
         credits: Amount,
+        referrer: Option<Owner>,
     ) -> Result<(), MarketError> {
+        let bought_at = self.runtime.system_time();
         self.runtime
             .prepare_message(Message::BuyNFT {
                 collection_id,
                 token_id,
                 credits,
+                referrer,
+                bought_at,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -280,8 +856,24 @@ impl MarketContract {
         &mut self,
         credits_per_linera: Amount,
     ) -> Result<(), MarketError> {
+        let updated_at = self.runtime.system_time();
         self.runtime
-            .prepare_message(Message::UpdateCreditsPerLinera { credits_per_linera })
+            .prepare_message(Message::UpdateCreditsPerLinera {
+                credits_per_linera,
+                updated_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_refresh_credits_per_linera(&mut self) -> Result<(), MarketError> {
+        let refreshed_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::RefreshCreditsPerLinera {
+                target: None,
+                refreshed_at,
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(())
@@ -342,10 +934,10 @@ impl MarketContract {
             .prepare_message(Message::RequestSubscribe)
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        // TODO: send instantiation argument to subscriber
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn on_op_create_collection(
         &mut self,
         base_uri: String,
@@ -353,7 +945,10 @@ impl MarketContract {
         name: String,
         uris: Vec<String>,
         publisher: Owner,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
     ) -> Result<(), MarketError> {
+        let created_at = self.runtime.system_time();
         self.runtime
             .prepare_message(Message::CreateCollection {
                 base_uri,
@@ -361,239 +956,1434 @@ impl MarketContract {
                 name,
                 uris,
                 publisher,
+                royalty_percent,
+                metadata,
+                created_at,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        // TODO: send instantiation argument to subscriber
         Ok(())
     }
 
-    async fn on_msg_instantiation_argument(
+    fn on_op_transfer_nft(
         &mut self,
-        argument: InstantiationArgument,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
     ) -> Result<(), MarketError> {
-        self.state.instantiate_market(argument).await;
+        self.runtime
+            .prepare_message(Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
         Ok(())
     }
 
-    async fn on_msg_create_collection(
-        &mut self,
-        base_uri: String,
-        price: Option<Amount>,
-        name: String,
-        uris: Vec<String>,
-        publisher: Owner,
-    ) -> Result<(), MarketError> {
-        self.state
-            .create_collection(
-                publisher,
-                base_uri.clone(),
-                price,
-                name.clone(),
-                uris.clone(),
-                self.runtime.system_time(),
-            )
-            .await?;
-        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
-            return Ok(());
-        }
-        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+    fn on_op_batch_transfer(&mut self, nfts: Vec<NFTKey>, to: Owner) -> Result<(), MarketError> {
         self.runtime
-            .prepare_message(Message::CreateCollection {
-                base_uri,
-                price,
-                name,
-                uris,
-                publisher,
-            })
+            .prepare_message(Message::BatchTransfer { nfts, to })
             .with_authentication()
-            .send_to(dest);
+            .send_to(self.runtime.application_id().creation.chain_id);
         Ok(())
     }
 
-    async fn on_msg_mint_nft(
+    #[allow(clippy::too_many_arguments)]
+    fn on_op_create_auction(
         &mut self,
         collection_id: u64,
-        uri_index: u16,
-        price: Option<Amount>,
-        name: String,
+        token_id: u16,
+        kind: AuctionKind,
+        reserve: Amount,
+        start_price: Option<Amount>,
+        start_at: Timestamp,
+        end_at: Timestamp,
     ) -> Result<(), MarketError> {
-        let owner = self.require_authenticated_signer()?;
-        self.state
-            .mint_nft(
-                owner,
-                collection_id,
-                uri_index,
-                price,
-                name.clone(),
-                self.runtime.system_time(),
-            )
-            .await?;
-        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
-            return Ok(());
-        }
-        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        let created_at = self.runtime.system_time();
         self.runtime
-            .prepare_message(Message::MintNFT {
+            .prepare_message(Message::CreateAuction {
                 collection_id,
-                uri_index,
-                price,
-                name,
+                token_id,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+                created_at,
             })
             .with_authentication()
-            .send_to(dest);
+            .send_to(self.runtime.application_id().creation.chain_id);
         Ok(())
     }
 
-    async fn on_msg_buy_nft(
+    fn on_op_bid(
         &mut self,
-        collection_id: u64,
-        token_id: u16,
+        auction_id: u64,
+        amount: Amount,
         credits: Amount,
     ) -> Result<(), MarketError> {
-        let buyer = self.require_authenticated_signer()?;
-        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
-            let owner = self.state.nft_owner(collection_id, token_id).await?;
-            let price = self.state.nft_price(collection_id, token_id).await?;
-            let fee = self.state.trading_fee(price).await?;
-            let discount = self.state.credits_to_tokens(credits).await?;
-            self.transfer_credits(buyer, owner, credits).await?;
-            self.transfer_tokens(
-                buyer,
-                owner,
-                price.saturating_sub(fee).saturating_sub(discount),
-            )
-            .await?;
-            self.deposit_commission(buyer, fee).await?;
-        }
-        self.state.buy_nft(buyer, collection_id, token_id).await?;
+        let bid_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::Bid {
+                auction_id,
+                amount,
+                credits,
+                bid_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_settle_auction(&mut self, auction_id: u64) -> Result<(), MarketError> {
+        let settled_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::SettleAuction {
+                auction_id,
+                settled_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_make_offer(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        credits: Amount,
+        expires_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let created_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                credits,
+                expires_at,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_cancel_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::CancelOffer { offer_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_accept_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        let accepted_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::AcceptOffer {
+                offer_id,
+                accepted_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_counter_offer(&mut self, offer_id: u64, price: Amount) -> Result<(), MarketError> {
+        let countered_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::CounterOffer {
+                offer_id,
+                price,
+                countered_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_accept_counter(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        let accepted_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::AcceptCounter {
+                offer_id,
+                accepted_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_public_mint(&mut self, collection_id: u64) -> Result<(), MarketError> {
+        let minted_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::PublicMint {
+                collection_id,
+                minted_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_create_bundle(&mut self, nfts: Vec<NFTKey>, price: Amount) -> Result<(), MarketError> {
+        let created_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::CreateBundle {
+                nfts,
+                price,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_buy_bundle(
+        &mut self,
+        bundle_id: u64,
+        credits: Amount,
+        referrer: Option<Owner>,
+    ) -> Result<(), MarketError> {
+        let bought_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::BuyBundle {
+                bundle_id,
+                credits,
+                referrer,
+                bought_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_cancel_bundle(&mut self, bundle_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::CancelBundle { bundle_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_list_editions(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        units: u16,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::ListEditions {
+                collection_id,
+                token_id,
+                units,
+                price,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_buy_editions(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        seller: Owner,
+        units: u16,
+        credits: Amount,
+        referrer: Option<Owner>,
+    ) -> Result<(), MarketError> {
+        let bought_at = self.runtime.system_time();
+        self.runtime
+            .prepare_message(Message::BuyEditions {
+                collection_id,
+                token_id,
+                seller,
+                units,
+                credits,
+                referrer,
+                bought_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_transfer_editions(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+        units: u16,
+    ) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::TransferEditions {
+                collection_id,
+                token_id,
+                to,
+                units,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_deposit(&mut self, amount: Amount) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::Deposit { amount })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_withdraw(&mut self, amount: Amount) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::Withdraw { amount })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_update_fee_schedule(&mut self, schedule: FeeSchedule) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::UpdateFeeSchedule { schedule })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_set_admin(&mut self, admin: Owner) -> Result<(), MarketError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(MarketError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetAdmin { admin })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    async fn on_msg_instantiation_argument(
+        &mut self,
+        argument: InstantiationArgument,
+    ) -> Result<(), MarketError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(MarketError::OperationNotAllowed);
+        }
+        self.state.instantiate_market(argument).await;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn on_msg_create_collection(
+        &mut self,
+        base_uri: String,
+        price: Option<Amount>,
+        name: String,
+        uris: Vec<String>,
+        publisher: Owner,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
+        created_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let created_at = self.message_time(created_at);
+        self.state
+            .create_collection(
+                publisher,
+                base_uri.clone(),
+                price,
+                name.clone(),
+                uris.clone(),
+                royalty_percent,
+                metadata.clone(),
+                created_at,
+            )
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CreateCollection {
+                base_uri,
+                price,
+                name,
+                uris,
+                publisher,
+                royalty_percent,
+                metadata,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn on_msg_mint_nft(
+        &mut self,
+        collection_id: u64,
+        uri_index: u16,
+        price: Option<Amount>,
+        name: String,
+        attributes: Vec<NFTAttribute>,
+        editions: u16,
+        minted_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        let minted_at = self.message_time(minted_at);
+        self.state
+            .mint_nft(
+                owner,
+                collection_id,
+                uri_index,
+                price,
+                name.clone(),
+                attributes.clone(),
+                editions,
+                minted_at,
+            )
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::MintNFT {
+                collection_id,
+                uri_index,
+                price,
+                name,
+                attributes,
+                editions,
+                minted_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_buy_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let bought_at = self.message_time(bought_at);
+        let owner = self.state.nft_owner(collection_id, token_id).await?;
+        let price = self.state.nft_price(collection_id, token_id).await?;
+        let royalty = self.charge_royalty(collection_id, owner, price).await?;
+        let fees = self.state.trading_fee(collection_id, price).await?;
+        self.pay_from_deposit(
+            buyer,
+            owner,
+            price,
+            credits,
+            fees,
+            royalty.into_iter().collect(),
+            referrer,
+        )
+        .await?;
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                owner,
+                NotificationKind::NFTSold,
+                format!("{}:{}", collection_id, token_id),
+                format!("Your NFT is sold at {} with {} credits", price, credits),
+            );
+        }
+        self.state.buy_nft(buyer, collection_id, token_id).await?;
+        self.state
+            .record_sale(Sale {
+                sale_id: 0,
+                kind: SaleKind::FixedPrice,
+                collection_id,
+                token_id,
+                seller: owner,
+                buyer,
+                price,
+                credits,
+                fee: fees.total(),
+                royalty: royalty.map_or(Amount::ZERO, |(_, royalty)| royalty),
+                created_at: bought_at,
+            })
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::BuyNFT {
+                collection_id,
+                token_id,
+                credits,
+                referrer,
+                bought_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_update_credits_per_linera(
+        &mut self,
+        credits_per_linera: Amount,
+        updated_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        self.require_admin()?;
+        let updated_at = self.message_time(updated_at);
+        self.state
+            .override_credits_per_linera(credits_per_linera, updated_at)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::UpdateCreditsPerLinera {
+                credits_per_linera,
+                updated_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    /// Credits outstanding against Linera held by foundation, both read on the creation chain
+    fn supply_credits_per_linera(&mut self) -> Result<Amount, MarketError> {
+        let credit_app_id = self.credit_app_id();
+        let outstanding = match self.runtime.call_application(
+            true,
+            credit_app_id,
+            &credit::Operation::OutstandingSupply,
+        ) {
+            CreditResponse::OutstandingSupply(amount) => amount,
+            _ => return Err(MarketError::InvalidRateSource),
+        };
+        let foundation_app_id = self.foundation_app_id();
+        let foundation = match self.runtime.call_application(
+            true,
+            foundation_app_id,
+            &foundation::Operation::FoundationBalance,
+        ) {
+            FoundationResponse::Balance(amount) => amount,
+            _ => return Err(MarketError::InvalidRateSource),
+        };
+        Market::supply_rate(outstanding, foundation).ok_or(MarketError::InvalidRateSource)
+    }
+
+    async fn on_msg_refresh_credits_per_linera(
+        &mut self,
+        target: Option<Amount>,
+        refreshed_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let refreshed_at = self.message_time(refreshed_at);
+        let target =
+            match self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+                true => self.supply_credits_per_linera()?,
+                false => target.ok_or(MarketError::InvalidRateSource)?,
+            };
+        self.state
+            .refresh_credits_per_linera(target, refreshed_at)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::RefreshCreditsPerLinera {
+                target: Some(target),
+                refreshed_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_update_nft_price(
+        &mut self,
+        collection_id: u64,
+        token_id: Option<u16>,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        let setter = self.require_authenticated_signer()?;
+        self.state
+            .update_nft_price(setter, collection_id, token_id, price)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::UpdateNFTPrice {
+                collection_id,
+                token_id,
+                price,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_on_sale_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), MarketError> {
+        let setter = self.require_authenticated_signer()?;
+        self.state
+            .on_sale_nft(setter, collection_id, token_id)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::OnSaleNFT {
+                collection_id,
+                token_id,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_off_sale_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), MarketError> {
+        let setter = self.require_authenticated_signer()?;
+        self.state
+            .off_sale_nft(setter, collection_id, token_id)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::OffSaleNFT {
+                collection_id,
+                token_id,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_set_avatar(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), MarketError> {
+        let setter = self.require_authenticated_signer()?;
+        self.state
+            .set_avatar(setter, collection_id, token_id)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetAvatar {
+                collection_id,
+                token_id,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_transfer_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state
+            .transfer_nft(owner, collection_id, token_id, to)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_batch_transfer(
+        &mut self,
+        nfts: Vec<NFTKey>,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        for nft in nfts.iter() {
+            self.state
+                .transfer_nft(owner, nft.collection_id, nft.token_id, to)
+                .await?;
+        }
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::BatchTransfer { nfts, to })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn on_msg_create_auction(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        reserve: Amount,
+        start_price: Option<Amount>,
+        start_at: Timestamp,
+        end_at: Timestamp,
+        created_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let seller = self.require_authenticated_signer()?;
+        let created_at = self.message_time(created_at);
+        self.state
+            .create_auction(
+                seller,
+                collection_id,
+                token_id,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+                created_at,
+            )
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_bid(
+        &mut self,
+        auction_id: u64,
+        amount: Amount,
+        credits: Amount,
+        bid_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let bidder = self.require_authenticated_signer()?;
+        let bid_at = self.message_time(bid_at);
+        let outbid = self
+            .state
+            .place_bid(bidder, auction_id, amount, credits, bid_at)
+            .await?;
+        // Tokens of bids are taken from the deposit on every chain so all of them agree
+        self.state.spend_balance(bidder, amount).await?;
+        if let Some(bid) = outbid.clone() {
+            self.state.refund_balance(bid.bidder, bid.amount).await?;
+        }
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        if creation_chain {
+            self.escrow(bidder, Amount::ZERO, credits).await?;
+            if let Some(bid) = outbid {
+                self.refund_escrow(bid.bidder, Amount::ZERO, bid.credits)
+                    .await?;
+                let notification_app_id = self.notification_app_id();
+                notification::notify(
+                    &mut self.runtime,
+                    notification_app_id,
+                    bid.bidder,
+                    NotificationKind::AuctionOutbid,
+                    format!("{}", auction_id),
+                    format!("Your bid {} is outbid by {}", bid.value, amount),
+                );
+            }
+        }
+        // Dutch auction is sold to the first bid at the current price
+        if self.state.auction(auction_id).await?.kind == AuctionKind::Dutch {
+            self.settle_auction(auction_id, bid_at).await?;
+        }
+        if !creation_chain {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Bid {
+                auction_id,
+                amount,
+                credits,
+                bid_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_settle_auction(
+        &mut self,
+        auction_id: u64,
+        settled_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let settled_at = self.message_time(settled_at);
+        self.settle_auction(auction_id, settled_at).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SettleAuction {
+                auction_id,
+                settled_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_make_offer(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        credits: Amount,
+        expires_at: Timestamp,
+        created_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let created_at = self.message_time(created_at);
+        let offer_id = self
+            .state
+            .make_offer(
+                buyer,
+                collection_id,
+                token_id,
+                amount,
+                credits,
+                expires_at,
+                created_at,
+            )
+            .await?;
+        // Tokens of offers are taken from the deposit on every chain so all of them agree
+        self.state.spend_balance(buyer, amount).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.escrow(buyer, Amount::ZERO, credits).await?;
+        let offer = self.state.offer(offer_id).await?;
+        let owner = self.state.nft_owner(collection_id, token_id).await?;
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            owner,
+            NotificationKind::OfferReceived,
+            format!("{}:{}", collection_id, token_id),
+            format!("You received offer {} of {}", offer_id, offer.value),
+        );
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                credits,
+                expires_at,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_cancel_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let offer = self.state.cancel_offer(buyer, offer_id).await?;
+        self.state.refund_balance(offer.buyer, offer.amount).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.refund_escrow(offer.buyer, Amount::ZERO, offer.credits)
+            .await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CancelOffer { offer_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_accept_offer(
+        &mut self,
+        offer_id: u64,
+        accepted_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        let accepted_at = self.message_time(accepted_at);
+        let offer = self
+            .state
+            .accept_offer(owner, offer_id, accepted_at)
+            .await?;
+        self.sell_to_offer(owner, &offer, accepted_at).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            offer.buyer,
+            NotificationKind::OfferAccepted,
+            format!("{}:{}", offer.collection_id, offer.token_id),
+            format!("Your offer {} of {} is accepted", offer_id, offer.value),
+        );
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::BuyNFT {
-                collection_id,
-                token_id,
-                credits,
+            .prepare_message(Message::AcceptOffer {
+                offer_id,
+                accepted_at,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    async fn on_msg_update_credits_per_linera(
+    /// Record the sale of an accepted offer and pay the escrow to the owner on creation chain
+    async fn sell_to_offer(
         &mut self,
-        credits_per_linera: Amount,
+        owner: Owner,
+        offer: &Offer,
+        accepted_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let royalty = self
+            .charge_royalty(offer.collection_id, owner, offer.value)
+            .await?;
+        // The schedule may have changed since the offer, fees never exceed what is escrowed
+        let fees = self
+            .state
+            .trading_fee(offer.collection_id, offer.value)
+            .await?
+            .capped(offer.amount);
+        self.state
+            .record_sale(Sale {
+                sale_id: 0,
+                kind: SaleKind::Offer,
+                collection_id: offer.collection_id,
+                token_id: offer.token_id,
+                seller: owner,
+                buyer: offer.buyer,
+                price: offer.value,
+                credits: offer.credits,
+                fee: fees.total(),
+                royalty: royalty.map_or(Amount::ZERO, |(_, royalty)| royalty),
+                created_at: accepted_at,
+            })
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.pay_from_escrow(owner, offer.amount, offer.credits, fees, royalty)
+            .await
+    }
+
+    async fn on_msg_accept_counter(
+        &mut self,
+        offer_id: u64,
+        accepted_at: Timestamp,
     ) -> Result<(), MarketError> {
-        // TODO: Only creation user can update
-        self.state.credits_per_linera.set(credits_per_linera);
+        let buyer = self.require_authenticated_signer()?;
+        let accepted_at = self.message_time(accepted_at);
+        let (offer, owner) = self
+            .state
+            .accept_counter(buyer, offer_id, accepted_at)
+            .await?;
+        self.sell_to_offer(owner, &offer, accepted_at).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            owner,
+            NotificationKind::NFTSold,
+            format!("{}:{}", offer.collection_id, offer.token_id),
+            format!(
+                "Your counter price {} of offer {} is accepted",
+                offer.value, offer_id
+            ),
+        );
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::UpdateCreditsPerLinera { credits_per_linera })
+            .prepare_message(Message::AcceptCounter {
+                offer_id,
+                accepted_at,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    async fn on_msg_update_nft_price(
+    async fn on_msg_counter_offer(
         &mut self,
-        collection_id: u64,
-        token_id: Option<u16>,
+        offer_id: u64,
         price: Amount,
+        countered_at: Timestamp,
     ) -> Result<(), MarketError> {
-        let setter = self.require_authenticated_signer()?;
-        self.state
-            .update_nft_price(setter, collection_id, token_id, price)
+        let owner = self.require_authenticated_signer()?;
+        let countered_at = self.message_time(countered_at);
+        let offer = self
+            .state
+            .counter_offer(owner, offer_id, price, countered_at)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            offer.buyer,
+            NotificationKind::OfferCountered,
+            format!("{}:{}", offer.collection_id, offer.token_id),
+            format!("Owner asks {} for your offer {}", price, offer_id),
+        );
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::UpdateNFTPrice {
+            .prepare_message(Message::CounterOffer {
+                offer_id,
+                price,
+                countered_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_public_mint(
+        &mut self,
+        collection_id: u64,
+        minted_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let minter = self.require_authenticated_signer()?;
+        let minted_at = self.message_time(minted_at);
+        let (token_id, price, publisher) = self
+            .state
+            .public_mint_nft(minter, collection_id, minted_at)
+            .await?;
+        // Publisher minting its own collection pays nothing
+        let fees = match minter == publisher {
+            true => SaleFees::default(),
+            false => self.state.trading_fee(collection_id, price).await?,
+        };
+        if minter != publisher {
+            self.pay_from_deposit(
+                minter,
+                publisher,
+                price,
+                Amount::ZERO,
+                fees,
+                Vec::new(),
+                None,
+            )
+            .await?;
+        }
+        self.state
+            .record_sale(Sale {
+                sale_id: 0,
+                kind: SaleKind::Mint,
                 collection_id,
                 token_id,
+                seller: publisher,
+                buyer: minter,
                 price,
+                credits: Amount::ZERO,
+                fee: fees.total(),
+                royalty: Amount::ZERO,
+                created_at: minted_at,
+            })
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            publisher,
+            NotificationKind::NFTSold,
+            format!("{}:{}", collection_id, token_id),
+            format!("Your NFT is minted at {}", price),
+        );
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::PublicMint {
+                collection_id,
+                minted_at,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    async fn on_msg_on_sale_nft(
+    async fn on_msg_create_bundle(
         &mut self,
-        collection_id: u64,
-        token_id: u16,
+        nfts: Vec<NFTKey>,
+        price: Amount,
+        created_at: Timestamp,
     ) -> Result<(), MarketError> {
-        let setter = self.require_authenticated_signer()?;
+        let seller = self.require_authenticated_signer()?;
+        let created_at = self.message_time(created_at);
         self.state
-            .on_sale_nft(setter, collection_id, token_id)
+            .create_bundle(seller, nfts.clone(), price, created_at)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::OnSaleNFT {
+            .prepare_message(Message::CreateBundle {
+                nfts,
+                price,
+                created_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_buy_bundle(
+        &mut self,
+        bundle_id: u64,
+        credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let bought_at = self.message_time(bought_at);
+        let bundle = self.state.buy_bundle(buyer, bundle_id, bought_at).await?;
+        let mut fees = SaleFees::default();
+        let mut royalties = Vec::new();
+        for (key, price) in bundle.nfts.iter().zip(bundle.token_prices()) {
+            let fee = self.state.trading_fee(key.collection_id, price).await?;
+            let royalty = self
+                .charge_royalty(key.collection_id, bundle.seller, price)
+                .await?;
+            royalties.extend(royalty);
+            fees = fees.saturating_add(fee);
+            self.state
+                .record_sale(Sale {
+                    sale_id: 0,
+                    kind: SaleKind::Bundle,
+                    collection_id: key.collection_id,
+                    token_id: key.token_id,
+                    seller: bundle.seller,
+                    buyer,
+                    price,
+                    credits: Amount::ZERO,
+                    fee: fee.total(),
+                    royalty: royalty.map_or(Amount::ZERO, |(_, royalty)| royalty),
+                    created_at: bought_at,
+                })
+                .await?;
+        }
+        self.pay_from_deposit(
+            buyer,
+            bundle.seller,
+            bundle.price,
+            credits,
+            fees,
+            royalties,
+            referrer,
+        )
+        .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            bundle.seller,
+            NotificationKind::NFTSold,
+            format!("bundle:{}", bundle_id),
+            format!(
+                "Your bundle of {} NFTs is sold at {} with {} credits",
+                bundle.nfts.len(),
+                bundle.price,
+                credits
+            ),
+        );
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::BuyBundle {
+                bundle_id,
+                credits,
+                referrer,
+                bought_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_cancel_bundle(&mut self, bundle_id: u64) -> Result<(), MarketError> {
+        let seller = self.require_authenticated_signer()?;
+        self.state.cancel_bundle(seller, bundle_id).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CancelBundle { bundle_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_list_editions(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        units: u16,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        let seller = self.require_authenticated_signer()?;
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        self.state.list_editions(seller, key, units, price).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ListEditions {
                 collection_id,
                 token_id,
+                units,
+                price,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    async fn on_msg_off_sale_nft(
+    #[allow(clippy::too_many_arguments)]
+    async fn on_msg_buy_editions(
         &mut self,
         collection_id: u64,
         token_id: u16,
+        seller: Owner,
+        units: u16,
+        credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
     ) -> Result<(), MarketError> {
-        let setter = self.require_authenticated_signer()?;
+        let buyer = self.require_authenticated_signer()?;
+        let bought_at = self.message_time(bought_at);
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let price = self.state.buy_editions(buyer, key, seller, units).await?;
+        let royalty = self.charge_royalty(collection_id, seller, price).await?;
+        let fees = self.state.trading_fee(collection_id, price).await?;
+        self.pay_from_deposit(
+            buyer,
+            seller,
+            price,
+            credits,
+            fees,
+            royalty.into_iter().collect(),
+            referrer,
+        )
+        .await?;
         self.state
-            .off_sale_nft(setter, collection_id, token_id)
+            .record_sale(Sale {
+                sale_id: 0,
+                kind: SaleKind::FixedPrice,
+                collection_id,
+                token_id,
+                seller,
+                buyer,
+                price,
+                credits,
+                fee: fees.total(),
+                royalty: royalty.map_or(Amount::ZERO, |(_, royalty)| royalty),
+                created_at: bought_at,
+            })
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            seller,
+            NotificationKind::NFTSold,
+            format!("{}:{}", collection_id, token_id),
+            format!(
+                "{} editions of your NFT are sold at {} with {} credits",
+                units, price, credits
+            ),
+        );
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::OffSaleNFT {
+            .prepare_message(Message::BuyEditions {
                 collection_id,
                 token_id,
+                seller,
+                units,
+                credits,
+                referrer,
+                bought_at,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    async fn on_msg_set_avatar(
+    async fn on_msg_transfer_editions(
         &mut self,
         collection_id: u64,
         token_id: u16,
+        to: Owner,
+        units: u16,
     ) -> Result<(), MarketError> {
-        let setter = self.require_authenticated_signer()?;
-        self.state
-            .set_avatar(setter, collection_id, token_id)
-            .await?;
+        let owner = self.require_authenticated_signer()?;
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        self.state.transfer_editions(owner, key, to, units).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::SetAvatar {
+            .prepare_message(Message::TransferEditions {
                 collection_id,
                 token_id,
+                to,
+                units,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
-    fn on_msg_request_subscribe(&mut self) -> Result<(), MarketError> {
+    async fn on_msg_deposit(&mut self, amount: Amount) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return self.state.deposit(owner, amount).await;
+        }
+        // Foundation shares the creation chain and applies the transfer in this transaction,
+        // so the deposit is only credited once the tokens are in escrow
+        let escrow = self.escrow_owner();
+        self.transfer_tokens(owner, escrow, amount).await?;
+        self.state.deposit(owner, amount).await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Deposit { amount })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_withdraw(&mut self, amount: Amount) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state.spend_balance(owner, amount).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.refund_escrow(owner, amount, Amount::ZERO).await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Withdraw { amount })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    /// Admin is only known to the creation chain, subscribers apply what it broadcasts
+    fn require_admin(&mut self) -> Result<(), MarketError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let signer = self.require_authenticated_signer()?;
+        match *self.state.admin.get() {
+            Some(admin) if admin == signer => Ok(()),
+            _ => Err(MarketError::NotAdmin),
+        }
+    }
+
+    /// Markets created before the admin existed have none until the creation chain claims it
+    fn on_msg_set_admin(&mut self, admin: Owner) -> Result<(), MarketError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(MarketError::OperationNotAllowed);
+        }
+        if self.state.admin.get().is_some() {
+            self.require_admin()?;
+        }
+        self.state.admin.set(Some(admin));
+        Ok(())
+    }
+
+    async fn on_msg_update_fee_schedule(
+        &mut self,
+        schedule: FeeSchedule,
+    ) -> Result<(), MarketError> {
+        self.require_admin()?;
+        self.state.update_fee_schedule(schedule.clone()).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::UpdateFeeSchedule { schedule })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_request_subscribe(&mut self) -> Result<(), MarketError> {
         let message_id = self.require_message_id()?;
         // The subscribe message must be from another chain
         if message_id.chain_id == self.runtime.application_id().creation.chain_id {
@@ -603,6 +2393,596 @@ impl MarketContract {
             message_id.chain_id,
             ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()),
         );
+        // Subscribers validate bids, offers and purchases against the same settings
+        self.runtime
+            .prepare_message(Message::InstantiationArgument {
+                argument: self.state.initial_state().await?,
+            })
+            .with_authentication()
+            .send_to(message_id.chain_id);
+        self.runtime
+            .prepare_message(Message::UpdateFeeSchedule {
+                schedule: self.state.fee_schedule.get().clone(),
+            })
+            .with_authentication()
+            .send_to(message_id.chain_id);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use linera_sdk::{
+        base::{Amount, Owner, Timestamp},
+        util::BlockingWait,
+        views::View,
+        ContractRuntime,
+    };
+    use market::{
+        AuctionKind, BundleStatus, CollectionMetadata, InstantiationArgument, MarketError, NFTKey,
+        SaleFees,
+    };
+
+    use super::{state::Market, MarketContract};
+
+    fn owner(id: u8) -> Owner {
+        format!("{:064x}", id).parse().unwrap()
+    }
+
+    fn create_market() -> Market {
+        let runtime = ContractRuntime::<MarketContract>::new();
+        let mut market = Market::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        market
+            .instantiate_market(InstantiationArgument {
+                credits_per_linera: Amount::from_tokens(30),
+                max_credits_percent: 30,
+                trade_fee_percent: 3,
+                collection_id: None,
+                max_royalty_percent: 20,
+                rate_epoch_ms: 1000,
+                max_rate_change_percent: 10,
+                rate_smoothing_percent: 50,
+            })
+            .blocking_wait();
+        market
+    }
+
+    /// Collection of publisher with a price of 10 tokens and no royalty
+    fn create_collection(market: &mut Market, publisher: Owner) -> u64 {
+        let collection_id = *market.collection_id.get();
+        market
+            .create_collection(
+                publisher,
+                format!("ipfs://collection-{}", collection_id),
+                Some(Amount::from_tokens(10)),
+                "Collection".to_string(),
+                vec!["0.png".to_string(), "1.png".to_string()],
+                0,
+                CollectionMetadata::default(),
+                Timestamp::from(0),
+            )
+            .blocking_wait()
+            .expect("Failed to create collection");
+        collection_id
+    }
+
+    fn mint_nft(
+        market: &mut Market,
+        publisher: Owner,
+        collection_id: u64,
+        editions: u16,
+    ) -> NFTKey {
+        let token_id = market
+            .token_ids
+            .get(&collection_id)
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        market
+            .mint_nft(
+                publisher,
+                collection_id,
+                0,
+                None,
+                format!("Token {}", token_id),
+                Vec::new(),
+                editions,
+                Timestamp::from(0),
+            )
+            .blocking_wait()
+            .expect("Failed to mint nft");
+        NFTKey {
+            collection_id,
+            token_id,
+        }
+    }
+
+    fn nft_owner(market: &Market, key: NFTKey) -> Owner {
+        market
+            .nft_owner(key.collection_id, key.token_id)
+            .blocking_wait()
+            .expect("Token should have an owner")
+    }
+
+    #[test]
+    fn migrate_nft_owners() {
+        let mut market = create_market();
+        let (alice, bob, carol) = (owner(1), owner(2), owner(3));
+        market.state_version.set(0);
+        market
+            .token_owners
+            .insert(&1000, HashMap::from([(1000, alice), (1001, bob)]))
+            .unwrap();
+        market
+            .token_owners
+            .insert(&1001, HashMap::from([(1000, bob)]))
+            .unwrap();
+        // Seller kept the token in assets of version 0 after it was bought by bob
+        market
+            ._assets
+            .insert(&carol, HashMap::from([(1000, vec![1001])]))
+            .unwrap();
+
+        market.migrate().blocking_wait().expect("Failed to migrate");
+
+        let key = |collection_id, token_id| NFTKey {
+            collection_id,
+            token_id,
+        };
+        assert_eq!(nft_owner(&market, key(1000, 1000)), alice);
+        assert_eq!(nft_owner(&market, key(1001, 1000)), bob);
+        assert_eq!(nft_owner(&market, key(1000, 1001)), bob);
+        assert_eq!(
+            market.assets(alice).blocking_wait(),
+            HashMap::from([(1000, vec![1000])])
+        );
+        let mut bob_assets = market.assets(bob).blocking_wait();
+        bob_assets
+            .values_mut()
+            .for_each(|token_ids| token_ids.sort());
+        assert_eq!(
+            bob_assets,
+            HashMap::from([(1000, vec![1001]), (1001, vec![1000])])
+        );
+        assert!(market
+            ._assets
+            .get(&carol)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        assert!(market
+            .token_owners
+            .indices()
+            .blocking_wait()
+            .unwrap()
+            .is_empty());
+        assert_eq!(*market.state_version.get(), 3);
+        assert_eq!(market.fee_schedule.get().default_rate.foundation_percent, 3);
+        assert_eq!(*market.rate_epoch_ms.get(), 86_400_000);
+    }
+
+    #[test]
+    fn quote_sale() {
+        let market = create_market();
+        let fees = SaleFees {
+            marketplace: Amount::from_tokens(2),
+            foundation: Amount::from_tokens(3),
+            referral: Amount::ZERO,
+        };
+
+        // 30 credits cover 1 token at 30 credits per linera
+        let quote = market
+            .quote_sale(
+                Amount::from_tokens(100),
+                Amount::from_tokens(30),
+                fees,
+                Amount::from_tokens(10),
+            )
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(quote.discount, Amount::from_tokens(1));
+        assert_eq!(quote.proceeds, Amount::from_tokens(84));
+        assert_eq!(quote.payment, Amount::from_tokens(99));
+
+        // Proceeds never go below zero when charges exceed the price
+        let quote = market
+            .quote_sale(Amount::from_tokens(4), Amount::ZERO, fees, Amount::ZERO)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(quote.proceeds, Amount::ZERO);
+        assert_eq!(quote.payment, Amount::from_tokens(5));
+    }
+
+    #[test]
+    fn place_english_bid() {
+        let mut market = create_market();
+        let (alice, bob, carol) = (owner(1), owner(2), owner(3));
+        let collection_id = create_collection(&mut market, alice);
+        let key = mint_nft(&mut market, alice, collection_id, 1);
+        let auction_id = market
+            .create_auction(
+                alice,
+                key.collection_id,
+                key.token_id,
+                AuctionKind::English,
+                Amount::from_tokens(10),
+                None,
+                Timestamp::from(100),
+                Timestamp::from(1000),
+                Timestamp::from(0),
+            )
+            .blocking_wait()
+            .unwrap();
+
+        let bid = |market: &mut Market, bidder: Owner, tokens: u128, credits: u128, now: u64| {
+            market
+                .place_bid(
+                    bidder,
+                    auction_id,
+                    Amount::from_tokens(tokens),
+                    Amount::from_tokens(credits),
+                    Timestamp::from(now),
+                )
+                .blocking_wait()
+        };
+        assert!(matches!(
+            bid(&mut market, bob, 10, 0, 50),
+            Err(MarketError::AuctionNotLive)
+        ));
+        assert!(matches!(
+            bid(&mut market, alice, 10, 0, 200),
+            Err(MarketError::BuyerIsOwner)
+        ));
+        assert!(matches!(
+            bid(&mut market, bob, 9, 0, 200),
+            Err(MarketError::InvalidBid)
+        ));
+        // Fees are only paid from escrowed tokens, a credits only bid could not cover them
+        assert!(matches!(
+            bid(&mut market, bob, 0, 300, 200),
+            Err(MarketError::InvalidBid)
+        ));
+        assert_eq!(bid(&mut market, bob, 10, 0, 200).unwrap(), None);
+        // Following bids must exceed the highest bid
+        assert!(matches!(
+            bid(&mut market, carol, 10, 0, 300),
+            Err(MarketError::InvalidBid)
+        ));
+        let outbid = bid(&mut market, carol, 10, 30, 300)
+            .unwrap()
+            .expect("Bid of bob should be outbid");
+        assert_eq!(outbid.bidder, bob);
+        assert!(matches!(
+            bid(&mut market, bob, 20, 0, 1000),
+            Err(MarketError::AuctionNotLive)
+        ));
+
+        assert!(matches!(
+            market
+                .settle_auction(auction_id, Timestamp::from(999))
+                .blocking_wait(),
+            Err(MarketError::AuctionNotEnded)
+        ));
+        let auction = market
+            .settle_auction(auction_id, Timestamp::from(1000))
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(auction.winner, Some(carol));
+        assert_eq!(nft_owner(&market, key), carol);
+    }
+
+    #[test]
+    fn place_dutch_bid() {
+        let mut market = create_market();
+        let (alice, bob, carol) = (owner(1), owner(2), owner(3));
+        let collection_id = create_collection(&mut market, alice);
+        let key = mint_nft(&mut market, alice, collection_id, 1);
+        assert!(matches!(
+            market
+                .create_auction(
+                    alice,
+                    key.collection_id,
+                    key.token_id,
+                    AuctionKind::Dutch,
+                    Amount::from_tokens(10),
+                    Some(Amount::from_tokens(10)),
+                    Timestamp::from(1000),
+                    Timestamp::from(2000),
+                    Timestamp::from(0),
+                )
+                .blocking_wait(),
+            Err(MarketError::InvalidAuction)
+        ));
+        let auction_id = market
+            .create_auction(
+                alice,
+                key.collection_id,
+                key.token_id,
+                AuctionKind::Dutch,
+                Amount::from_tokens(10),
+                Some(Amount::from_tokens(100)),
+                Timestamp::from(1000),
+                Timestamp::from(2000),
+                Timestamp::from(0),
+            )
+            .blocking_wait()
+            .unwrap();
+
+        // Price falls linearly from 100 to 10 over the auction
+        let auction = market.auction(auction_id).blocking_wait().unwrap();
+        assert_eq!(
+            market.auction_price(&auction, Timestamp::from(1000)),
+            Amount::from_tokens(100)
+        );
+        assert_eq!(
+            market.auction_price(&auction, Timestamp::from(1500)),
+            Amount::from_tokens(55)
+        );
+        assert_eq!(
+            market.auction_price(&auction, Timestamp::from(3000)),
+            Amount::from_tokens(10)
+        );
+
+        let bid = |market: &mut Market, bidder: Owner, tokens: u128| {
+            market
+                .place_bid(
+                    bidder,
+                    auction_id,
+                    Amount::from_tokens(tokens),
+                    Amount::ZERO,
+                    Timestamp::from(1500),
+                )
+                .blocking_wait()
+        };
+        assert!(matches!(
+            bid(&mut market, bob, 50),
+            Err(MarketError::InvalidBid)
+        ));
+        assert_eq!(bid(&mut market, bob, 55).unwrap(), None);
+        // First bid at the current price wins
+        assert!(matches!(
+            bid(&mut market, carol, 60),
+            Err(MarketError::AuctionNotLive)
+        ));
+
+        let auction = market
+            .settle_auction(auction_id, Timestamp::from(1600))
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(auction.winner, Some(bob));
+        assert_eq!(nft_owner(&market, key), bob);
+    }
+
+    #[test]
+    fn buy_bundle_is_atomic() {
+        let mut market = create_market();
+        let (alice, bob, carol) = (owner(1), owner(2), owner(3));
+        let collection_id = create_collection(&mut market, alice);
+        let first = mint_nft(&mut market, alice, collection_id, 1);
+        let second = mint_nft(&mut market, alice, collection_id, 1);
+        let bundle_id = market
+            .create_bundle(
+                alice,
+                vec![first, second],
+                Amount::from_tokens(20),
+                Timestamp::from(0),
+            )
+            .blocking_wait()
+            .unwrap();
+        market
+            .set_avatar(alice, first.collection_id, first.token_id)
+            .blocking_wait()
+            .unwrap();
+
+        // Second token changed hands behind the bundle, nothing moves
+        market
+            .transfer_nft_ownership(second, carol)
+            .blocking_wait()
+            .unwrap();
+        assert!(matches!(
+            market
+                .buy_bundle(bob, bundle_id, Timestamp::from(1))
+                .blocking_wait(),
+            Err(MarketError::NotTokenOwner)
+        ));
+        assert_eq!(nft_owner(&market, first), alice);
+        assert_eq!(
+            market.bundle(bundle_id).blocking_wait().unwrap().status,
+            BundleStatus::Listed
+        );
+        assert!(market
+            .nft_bundles
+            .contains_key(&first)
+            .blocking_wait()
+            .unwrap());
+        assert!(market
+            .avatars
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_some());
+
+        market
+            .transfer_nft_ownership(second, alice)
+            .blocking_wait()
+            .unwrap();
+        assert!(matches!(
+            market
+                .buy_bundle(alice, bundle_id, Timestamp::from(1))
+                .blocking_wait(),
+            Err(MarketError::BuyerIsOwner)
+        ));
+        let bundle = market
+            .buy_bundle(bob, bundle_id, Timestamp::from(1))
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(bundle.status, BundleStatus::Sold);
+        assert_eq!(bundle.buyer, Some(bob));
+        assert_eq!(nft_owner(&market, first), bob);
+        assert_eq!(nft_owner(&market, second), bob);
+        assert!(!market
+            .nft_bundles
+            .contains_key(&first)
+            .blocking_wait()
+            .unwrap());
+        assert!(!market
+            .nft_bundles
+            .contains_key(&second)
+            .blocking_wait()
+            .unwrap());
+        assert!(market
+            ._assets
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        assert!(market
+            .avatars
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            market
+                .buy_bundle(carol, bundle_id, Timestamp::from(2))
+                .blocking_wait(),
+            Err(MarketError::BundleNotListed)
+        ));
+    }
+
+    #[test]
+    fn move_editions() {
+        let mut market = create_market();
+        let (alice, bob, carol) = (owner(1), owner(2), owner(3));
+        let collection_id = create_collection(&mut market, alice);
+        // Minted on sale, all 5 units are listed at the collection price
+        let key = mint_nft(&mut market, alice, collection_id, 5);
+        market
+            .set_avatar(alice, key.collection_id, key.token_id)
+            .blocking_wait()
+            .unwrap();
+        let listed_units = |market: &Market, seller: Owner| {
+            market
+                .edition_listings
+                .get(&key)
+                .blocking_wait()
+                .unwrap()
+                .and_then(|listings| listings.get(&seller).map(|listing| listing.units))
+        };
+        let balance = |market: &Market, holder: Owner| {
+            market.edition_balance(key, holder).blocking_wait().unwrap()
+        };
+        assert_eq!(listed_units(&market, alice), Some(5));
+
+        assert!(matches!(
+            market.transfer_editions(alice, key, bob, 0).blocking_wait(),
+            Err(MarketError::InvalidEditions)
+        ));
+        assert!(matches!(
+            market.transfer_editions(alice, key, bob, 6).blocking_wait(),
+            Err(MarketError::InsufficientEditions)
+        ));
+        market
+            .transfer_editions(alice, key, bob, 3)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(balance(&market, alice), 2);
+        assert_eq!(balance(&market, bob), 3);
+        // Listing of the sender shrinks to what is left
+        assert_eq!(listed_units(&market, alice), Some(2));
+        assert!(market
+            .avatars
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_some());
+
+        let price = market
+            .buy_editions(carol, key, alice, 2)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(price, Amount::from_tokens(20));
+        assert_eq!(balance(&market, alice), 0);
+        assert_eq!(balance(&market, carol), 2);
+        assert_eq!(listed_units(&market, alice), None);
+        // Token stops being asset and avatar of alice once all units are gone
+        assert!(market
+            ._assets
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        assert!(market
+            .avatars
+            .get(&alice)
+            .blocking_wait()
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            market.assets(carol).blocking_wait(),
+            HashMap::from([(collection_id, vec![key.token_id])])
+        );
+
+        market
+            .list_editions(bob, key, 3, Amount::from_tokens(4))
+            .blocking_wait()
+            .unwrap();
+        market
+            .transfer_editions(bob, key, carol, 2)
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(listed_units(&market, bob), Some(1));
+        assert!(matches!(
+            market.buy_editions(carol, key, bob, 2).blocking_wait(),
+            Err(MarketError::InsufficientEditions)
+        ));
+    }
+
+    #[test]
+    fn refresh_credits_per_linera_bounds() {
+        let mut market = create_market();
+        let rate = |market: &mut Market, target: Amount, now: u64| {
+            market
+                .refresh_credits_per_linera(target, Timestamp::from(now))
+                .blocking_wait()
+        };
+
+        // Half way to target is 65, but the rate moves at most 10% per epoch
+        assert_eq!(
+            rate(&mut market, Amount::from_tokens(100), 0).unwrap(),
+            Amount::from_tokens(33)
+        );
+        assert!(matches!(
+            rate(&mut market, Amount::from_tokens(100), 999_999),
+            Err(MarketError::RateEpochNotEnded)
+        ));
+        // Close to target the smoothing step is smaller than the bound
+        assert_eq!(
+            rate(&mut market, Amount::from_tokens(34), 1_000_000).unwrap(),
+            Amount::from_millis(33_500)
+        );
+        assert_eq!(
+            rate(&mut market, Amount::ZERO, 2_000_000).unwrap(),
+            Amount::from_millis(30_150)
+        );
+        assert_eq!(
+            *market.credits_per_linera.get(),
+            Amount::from_millis(30_150)
+        );
+        assert_eq!(*market.rate_id.get(), 3);
+
+        // Admin override is not bounded and leaves the oracle epoch alone
+        market
+            .override_credits_per_linera(Amount::from_tokens(1), Timestamp::from(2_000_001))
+            .blocking_wait()
+            .unwrap();
+        assert!(matches!(
+            rate(&mut market, Amount::from_tokens(1), 2_000_002),
+            Err(MarketError::RateEpochNotEnded)
+        ));
+    }
+}
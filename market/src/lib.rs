@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, InputObject, Request, Response, SimpleObject};
 use linera_sdk::{
     base::{Amount, ApplicationId, ArithmeticError, ContractAbi, Owner, ServiceAbi, Timestamp},
     graphql::GraphQLMutationRoot,
@@ -24,6 +24,25 @@ impl ServiceAbi for MarketAbi {
 pub struct MarketParameters {
     pub credit_app_id: ApplicationId<credit::CreditAbi>,
     pub foundation_app_id: ApplicationId<foundation::FoundationAbi>,
+    pub notification_app_id: ApplicationId<notification::NotificationAbi>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "NFTAttributeInput")]
+pub struct NFTAttribute {
+    pub key: String,
+    pub value: String,
+    /// Set for numeric traits like level or power
+    pub numeric: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct NFTRarity {
+    pub token_id: u16,
+    /// Sum of the inverse frequency of each trait, multiplied by 100
+    pub score: u64,
+    /// Starts from 1 for the rarest token
+    pub rank: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -37,6 +56,83 @@ pub struct NFT {
     pub on_sale: bool,
     pub minted_at: Timestamp,
     pub name: String,
+    pub attributes: Vec<NFTAttribute>,
+    /// Number of copies, unique tokens have one
+    pub editions: u16,
+}
+
+impl NFT {
+    pub fn is_edition(&self) -> bool {
+        self.editions > 1
+    }
+}
+
+/// Edition units of a token put on sale by one holder
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct EditionListing {
+    pub seller: Owner,
+    pub units: u16,
+    /// Price of one unit in Linera token
+    pub price: Amount,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct EditionBalance {
+    pub owner: Owner,
+    pub units: u16,
+}
+
+/// Identifies a single token across all collections
+#[derive(
+    Debug,
+    Deserialize,
+    Serialize,
+    Copy,
+    Clone,
+    SimpleObject,
+    InputObject,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+)]
+#[graphql(input_name = "NFTKeyInput")]
+pub struct NFTKey {
+    pub collection_id: u64,
+    pub token_id: u16,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Default, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "CollectionMetadataInput")]
+pub struct CollectionMetadata {
+    pub description: Option<String>,
+    pub cover_image: Option<String>,
+    pub external_link: Option<String>,
+    pub max_supply: Option<u16>,
+    pub mint_start_at: Option<Timestamp>,
+    pub mint_end_at: Option<Timestamp>,
+    /// Tokens one owner could mint in the collection
+    pub mint_limit_per_owner: Option<u16>,
+    /// Anyone could mint the next uri at the collection price
+    pub public_mint: Option<bool>,
+}
+
+impl CollectionMetadata {
+    pub fn is_valid(&self) -> bool {
+        if let (Some(start_at), Some(end_at)) = (self.mint_start_at, self.mint_end_at) {
+            if end_at <= start_at {
+                return false;
+            }
+        }
+        self.max_supply != Some(0) && self.mint_limit_per_owner != Some(0)
+    }
+
+    pub fn is_public_mint(&self) -> bool {
+        self.public_mint.unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -49,6 +145,363 @@ pub struct Collection {
     pub name: String,
     pub created_at: Timestamp,
     pub publisher: Owner,
+    /// Percent of secondary sales paid to the publisher
+    pub royalty_percent: u8,
+    pub metadata: CollectionMetadata,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum AuctionKind {
+    /// Ascending bids, highest bid at end_at wins
+    English,
+    /// Price falls from start_price to reserve, first bid at the current price wins
+    Dutch,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Live,
+    Settled,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct AuctionBid {
+    pub bidder: Owner,
+    /// Linera token taken from the deposit of the bidder
+    pub amount: Amount,
+    /// Escrowed credits
+    pub credits: Amount,
+    /// Bid in Linera token with credits converted at credits_per_linera
+    pub value: Amount,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub collection_id: u64,
+    pub token_id: u16,
+    pub seller: Owner,
+    pub kind: AuctionKind,
+    pub reserve: Amount,
+    /// Only for dutch auction
+    pub start_price: Option<Amount>,
+    pub start_at: Timestamp,
+    pub end_at: Timestamp,
+    pub bids: Vec<AuctionBid>,
+    pub status: AuctionStatus,
+    pub winner: Option<Owner>,
+    pub created_at: Timestamp,
+    pub settled_at: Option<Timestamp>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum OfferStatus {
+    Open,
+    Accepted,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Offer {
+    pub offer_id: u64,
+    pub collection_id: u64,
+    pub token_id: u16,
+    pub buyer: Owner,
+    /// Linera token taken from the deposit of the buyer
+    pub amount: Amount,
+    /// Escrowed credits
+    pub credits: Amount,
+    /// Offer in Linera token with credits converted at credits_per_linera
+    pub value: Amount,
+    /// Price the token owner asked for instead
+    pub counter: Option<Amount>,
+    /// Owner asked the counter price, the counter is void once the token changes hands
+    pub countered_by: Option<Owner>,
+    pub status: OfferStatus,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum SaleKind {
+    Mint,
+    FixedPrice,
+    Auction,
+    Offer,
+    Bundle,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum BundleStatus {
+    Listed,
+    Sold,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Bundle {
+    pub bundle_id: u64,
+    pub seller: Owner,
+    pub nfts: Vec<NFTKey>,
+    /// Price of the whole bundle in Linera token
+    pub price: Amount,
+    pub status: BundleStatus,
+    pub buyer: Option<Owner>,
+    pub created_at: Timestamp,
+    pub sold_at: Option<Timestamp>,
+}
+
+impl Bundle {
+    /// Bundle price split evenly across tokens, remainder goes to the last one
+    pub fn token_prices(&self) -> Vec<Amount> {
+        let count = self.nfts.len() as u128;
+        if count == 0 {
+            return Vec::new();
+        }
+        let price: u128 = self.price.into();
+        let mut prices = vec![Amount::from_attos(price / count); count as usize];
+        if let Some(last) = prices.last_mut() {
+            *last = last.saturating_add(Amount::from_attos(price % count));
+        }
+        prices
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Sale {
+    pub sale_id: u64,
+    pub kind: SaleKind,
+    pub collection_id: u64,
+    pub token_id: u16,
+    pub seller: Owner,
+    pub buyer: Owner,
+    /// Price in Linera token, credits included at credits_per_linera
+    pub price: Amount,
+    pub credits: Amount,
+    pub fee: Amount,
+    pub royalty: Amount,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct SalesVolume {
+    pub sales: u32,
+    pub volume: Amount,
+}
+
+/// Shares of the sale price taken as fees, in percent
+#[derive(
+    Debug, Deserialize, Serialize, Copy, Clone, Default, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "FeeRateInput")]
+pub struct FeeRate {
+    /// Paid to the marketplace fee recipient
+    pub marketplace_percent: u8,
+    /// Deposited to foundation
+    pub foundation_percent: u8,
+    /// Paid to the referrer named by buyer, deposited to foundation without one
+    pub referral_percent: u8,
+}
+
+impl FeeRate {
+    pub fn total_percent(&self) -> u16 {
+        self.marketplace_percent as u16
+            + self.foundation_percent as u16
+            + self.referral_percent as u16
+    }
+
+    fn share(percent: u8, price: Amount) -> Amount {
+        Amount::from_attos(
+            Amount::from_attos(percent as u128)
+                .saturating_mul(price.into())
+                .saturating_div(Amount::from_attos(100)),
+        )
+    }
+
+    pub fn fees(&self, price: Amount) -> SaleFees {
+        SaleFees {
+            marketplace: Self::share(self.marketplace_percent, price),
+            foundation: Self::share(self.foundation_percent, price),
+            referral: Self::share(self.referral_percent, price),
+        }
+    }
+}
+
+/// Rate of sales in one collection or price range, any unset bound matches
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "FeeTierInput")]
+pub struct FeeTier {
+    pub collection_id: Option<u64>,
+    pub min_price: Option<Amount>,
+    /// Exclusive
+    pub max_price: Option<Amount>,
+    pub rate: FeeRate,
+}
+
+impl FeeTier {
+    pub fn matches(&self, collection_id: u64, price: Amount) -> bool {
+        self.collection_id.map_or(true, |id| id == collection_id)
+            && self.min_price.map_or(true, |min_price| price >= min_price)
+            && self.max_price.map_or(true, |max_price| price < max_price)
+    }
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Default, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "FeeScheduleInput")]
+pub struct FeeSchedule {
+    pub default_rate: FeeRate,
+    /// First matching tier wins
+    pub tiers: Vec<FeeTier>,
+    /// Marketplace share is deposited to foundation when not set
+    pub marketplace_recipient: Option<Owner>,
+}
+
+impl FeeSchedule {
+    /// Every rate leaves room for the largest royalty and credits discount of a sale
+    pub fn is_valid(&self, max_royalty_percent: u8, max_credits_percent: u8) -> bool {
+        let max_percent =
+            100u16.saturating_sub(max_royalty_percent as u16 + max_credits_percent as u16);
+        self.default_rate.total_percent() <= max_percent
+            && self.tiers.iter().all(|tier| {
+                tier.rate.total_percent() <= max_percent
+                    && match (tier.min_price, tier.max_price) {
+                        (Some(min_price), Some(max_price)) => min_price < max_price,
+                        _ => true,
+                    }
+            })
+    }
+
+    pub fn rate(&self, collection_id: u64, price: Amount) -> FeeRate {
+        self.tiers
+            .iter()
+            .find(|tier| tier.matches(collection_id, price))
+            .map_or(self.default_rate, |tier| tier.rate)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Default, SimpleObject, Eq, PartialEq)]
+pub struct SaleFees {
+    pub marketplace: Amount,
+    pub foundation: Amount,
+    pub referral: Amount,
+}
+
+impl SaleFees {
+    pub fn total(&self) -> Amount {
+        self.marketplace
+            .saturating_add(self.foundation)
+            .saturating_add(self.referral)
+    }
+
+    pub fn saturating_add(self, other: SaleFees) -> SaleFees {
+        SaleFees {
+            marketplace: self.marketplace.saturating_add(other.marketplace),
+            foundation: self.foundation.saturating_add(other.foundation),
+            referral: self.referral.saturating_add(other.referral),
+        }
+    }
+
+    /// Shares reduced in order so that their total stays within limit
+    pub fn capped(self, limit: Amount) -> SaleFees {
+        let marketplace = self.marketplace.min(limit);
+        let limit = limit.saturating_sub(marketplace);
+        let foundation = self.foundation.min(limit);
+        let limit = limit.saturating_sub(foundation);
+        SaleFees {
+            marketplace,
+            foundation,
+            referral: self.referral.min(limit),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum RateSource {
+    Oracle,
+    Admin,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct CreditsPerLineraRate {
+    pub credits_per_linera: Amount,
+    /// Rate derived from credit supply the update moved towards, none for admin override
+    pub target: Option<Amount>,
+    pub source: RateSource,
+    pub updated_at: Timestamp,
+}
+
+/// What a purchase costs the buyer and pays the seller
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Quote {
+    pub price: Amount,
+    pub credits: Amount,
+    /// Tokens covered by the credits
+    pub discount: Amount,
+    pub fees: SaleFees,
+    pub royalty: Amount,
+    /// Tokens paid to the seller
+    pub proceeds: Amount,
+    /// Tokens taken from the buyer's deposit
+    pub payment: Amount,
+}
+
+impl Collection {
+    /// Full location of the uri, base_uri joined with uris[uri_index]
+    pub fn uri(&self, uri_index: u16) -> Option<String> {
+        self.uris.get(uri_index as usize).map(|uri| {
+            format!(
+                "{}/{}",
+                self.base_uri.trim_end_matches('/'),
+                uri.trim_start_matches('/')
+            )
+        })
+    }
+}
+
+/// Attribute in the metadata layout used by wallets and indexers
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct MetadataAttribute {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// Token metadata in the layout used by wallets and indexers
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct NFTMetadataDocument {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_url: Option<String>,
+    pub attributes: Vec<MetadataAttribute>,
+    /// Editions held by several owners have no single owner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+    pub collection: String,
+    pub collection_id: u64,
+    pub token_id: u16,
+}
+
+/// Collection level metadata in the layout used by wallets and indexers
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CollectionMetadataDocument {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_link: Option<String>,
+    /// Royalty in basis points
+    pub seller_fee_basis_points: u16,
+    pub fee_recipient: Owner,
+    pub collection_id: u64,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -57,6 +510,12 @@ pub struct InstantiationArgument {
     pub max_credits_percent: u8,
     pub trade_fee_percent: u8,
     pub collection_id: Option<u64>,
+    pub max_royalty_percent: u8,
+    /// Oracle updates the rate at most once per epoch
+    pub rate_epoch_ms: u64,
+    pub max_rate_change_percent: u8,
+    /// How far each update moves the rate towards the supply derived one
+    pub rate_smoothing_percent: u8,
 }
 
 #[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
@@ -67,15 +526,19 @@ pub enum Operation {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        attributes: Option<Vec<NFTAttribute>>,
+        editions: Option<u16>,
     },
     BuyNFT {
         collection_id: u64,
         token_id: u16,
         credits: Amount,
+        referrer: Option<Owner>,
     },
     UpdateCreditsPerLinera {
         credits_per_linera: Amount,
     },
+    RefreshCreditsPerLinera,
     UpdateNFTPrice {
         collection_id: u64,
         token_id: Option<u16>,
@@ -100,6 +563,102 @@ pub enum Operation {
         name: String,
         uris: Vec<String>,
         publisher: Owner,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
+    },
+    TransferNFT {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    },
+    BatchTransfer {
+        nfts: Vec<NFTKey>,
+        to: Owner,
+    },
+    CreateAuction {
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        reserve: Amount,
+        start_price: Option<Amount>,
+        start_at: Timestamp,
+        end_at: Timestamp,
+    },
+    Bid {
+        auction_id: u64,
+        amount: Amount,
+        credits: Amount,
+    },
+    SettleAuction {
+        auction_id: u64,
+    },
+    MakeOffer {
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        credits: Amount,
+        expires_at: Timestamp,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    AcceptOffer {
+        offer_id: u64,
+    },
+    CounterOffer {
+        offer_id: u64,
+        price: Amount,
+    },
+    AcceptCounter {
+        offer_id: u64,
+    },
+    PublicMint {
+        collection_id: u64,
+    },
+    CreateBundle {
+        nfts: Vec<NFTKey>,
+        price: Amount,
+    },
+    BuyBundle {
+        bundle_id: u64,
+        credits: Amount,
+        referrer: Option<Owner>,
+    },
+    CancelBundle {
+        bundle_id: u64,
+    },
+    ListEditions {
+        collection_id: u64,
+        token_id: u16,
+        units: u16,
+        price: Amount,
+    },
+    BuyEditions {
+        collection_id: u64,
+        token_id: u16,
+        seller: Owner,
+        units: u16,
+        credits: Amount,
+        referrer: Option<Owner>,
+    },
+    TransferEditions {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+        units: u16,
+    },
+    Deposit {
+        amount: Amount,
+    },
+    Withdraw {
+        amount: Amount,
+    },
+    UpdateFeeSchedule {
+        schedule: FeeSchedule,
+    },
+    /// Creation chain owner claims an unset admin, the admin hands it over
+    SetAdmin {
+        admin: Owner,
     },
 }
 
@@ -115,20 +674,34 @@ pub enum Message {
         name: String,
         uris: Vec<String>,
         publisher: Owner,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
+        created_at: Timestamp,
     },
     MintNFT {
         collection_id: u64,
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        attributes: Vec<NFTAttribute>,
+        editions: u16,
+        minted_at: Timestamp,
     },
     BuyNFT {
         collection_id: u64,
         token_id: u16,
         credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
     },
     UpdateCreditsPerLinera {
         credits_per_linera: Amount,
+        updated_at: Timestamp,
+    },
+    /// Target is derived by the creation chain and carried to subscribers
+    RefreshCreditsPerLinera {
+        target: Option<Amount>,
+        refreshed_at: Timestamp,
     },
     UpdateNFTPrice {
         collection_id: u64,
@@ -148,6 +721,111 @@ pub enum Message {
         token_id: u16,
     },
     RequestSubscribe,
+    TransferNFT {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    },
+    BatchTransfer {
+        nfts: Vec<NFTKey>,
+        to: Owner,
+    },
+    /// Timestamps are decided by the creation chain and replayed by subscribers
+    CreateAuction {
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        reserve: Amount,
+        start_price: Option<Amount>,
+        start_at: Timestamp,
+        end_at: Timestamp,
+        created_at: Timestamp,
+    },
+    Bid {
+        auction_id: u64,
+        amount: Amount,
+        credits: Amount,
+        bid_at: Timestamp,
+    },
+    SettleAuction {
+        auction_id: u64,
+        settled_at: Timestamp,
+    },
+    MakeOffer {
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        credits: Amount,
+        expires_at: Timestamp,
+        created_at: Timestamp,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    AcceptOffer {
+        offer_id: u64,
+        accepted_at: Timestamp,
+    },
+    CounterOffer {
+        offer_id: u64,
+        price: Amount,
+        countered_at: Timestamp,
+    },
+    AcceptCounter {
+        offer_id: u64,
+        accepted_at: Timestamp,
+    },
+    PublicMint {
+        collection_id: u64,
+        minted_at: Timestamp,
+    },
+    CreateBundle {
+        nfts: Vec<NFTKey>,
+        price: Amount,
+        created_at: Timestamp,
+    },
+    BuyBundle {
+        bundle_id: u64,
+        credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
+    },
+    CancelBundle {
+        bundle_id: u64,
+    },
+    ListEditions {
+        collection_id: u64,
+        token_id: u16,
+        units: u16,
+        price: Amount,
+    },
+    BuyEditions {
+        collection_id: u64,
+        token_id: u16,
+        seller: Owner,
+        units: u16,
+        credits: Amount,
+        referrer: Option<Owner>,
+        bought_at: Timestamp,
+    },
+    TransferEditions {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+        units: u16,
+    },
+    Deposit {
+        amount: Amount,
+    },
+    Withdraw {
+        amount: Amount,
+    },
+    UpdateFeeSchedule {
+        schedule: FeeSchedule,
+    },
+    SetAdmin {
+        admin: Owner,
+    },
 }
 
 /// An error that can occur during the contract execution.
@@ -211,4 +889,100 @@ pub enum MarketError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Receiver is same as owner")]
+    ReceiverIsOwner,
+
+    #[error("NFT is in auction")]
+    NFTInAuction,
+
+    #[error("Invalid auction")]
+    InvalidAuction,
+
+    #[error("Auction not exists")]
+    AuctionNotExists,
+
+    #[error("Auction not live")]
+    AuctionNotLive,
+
+    #[error("Auction not ended")]
+    AuctionNotEnded,
+
+    #[error("Invalid bid")]
+    InvalidBid,
+
+    #[error("Invalid offer")]
+    InvalidOffer,
+
+    #[error("Offer not exists")]
+    OfferNotExists,
+
+    #[error("Offer not open")]
+    OfferNotOpen,
+
+    #[error("Offer expired")]
+    OfferExpired,
+
+    #[error("Invalid collection metadata")]
+    InvalidMetadata,
+
+    #[error("Mint window closed")]
+    MintWindowClosed,
+
+    #[error("Max supply reached")]
+    MaxSupplyReached,
+
+    #[error("Mint limit reached")]
+    MintLimitReached,
+
+    #[error("Public mint not enabled")]
+    PublicMintNotEnabled,
+
+    #[error("Collection is only minted publicly")]
+    PublicMintOnly,
+
+    #[error("Invalid attributes")]
+    InvalidAttributes,
+
+    #[error("NFT is in bundle")]
+    NFTInBundle,
+
+    #[error("Invalid bundle")]
+    InvalidBundle,
+
+    #[error("Bundle not exists")]
+    BundleNotExists,
+
+    #[error("Bundle not listed")]
+    BundleNotListed,
+
+    #[error("Invalid editions")]
+    InvalidEditions,
+
+    #[error("NFT is not edition")]
+    NFTNotEdition,
+
+    #[error("Insufficient editions")]
+    InsufficientEditions,
+
+    #[error("Insufficient balance")]
+    InsufficientBalance,
+
+    #[error("Invalid amount")]
+    InvalidAmount,
+
+    #[error("Not admin")]
+    NotAdmin,
+
+    #[error("Invalid fee schedule")]
+    InvalidFeeSchedule,
+
+    #[error("Invalid referrer")]
+    InvalidReferrer,
+
+    #[error("Rate epoch not ended")]
+    RateEpochNotEnded,
+
+    #[error("Invalid rate source")]
+    InvalidRateSource,
 }
@@ -8,10 +8,11 @@ use linera_sdk::{
     base::WithServiceAbi, graphql::GraphQLMutationRoot, views::View, Service, ServiceRuntime,
 };
 use market::{MarketParameters, Operation};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub struct MarketService {
     state: Arc<Market>,
+    runtime: Arc<Mutex<ServiceRuntime<MarketService>>>,
 }
 
 linera_sdk::service!(MarketService);
@@ -29,6 +30,7 @@ impl Service for MarketService {
             .expect("Failed to load state");
         MarketService {
             state: Arc::new(state),
+            runtime: Arc::new(Mutex::new(runtime)),
         }
     }
 
@@ -38,6 +40,7 @@ impl Service for MarketService {
             Operation::mutation_root(),
             EmptySubscription,
         )
+        .data(self.runtime.lock().unwrap().system_time())
         .finish();
         schema.execute(request).await
     }
@@ -1,33 +1,492 @@
 use std::collections::HashMap;
 
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, Context, SimpleObject};
 use linera_sdk::{
     base::{Amount, Owner, Timestamp},
     views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
 };
-use market::{Collection, InstantiationArgument, MarketError, NFT};
+use market::{
+    Auction, AuctionBid, AuctionKind, AuctionStatus, Bundle, BundleStatus, Collection,
+    CollectionMetadata, CollectionMetadataDocument, CreditsPerLineraRate, EditionBalance,
+    EditionListing, FeeRate, FeeSchedule, InstantiationArgument, MarketError, MetadataAttribute,
+    NFTAttribute, NFTKey, NFTMetadataDocument, NFTRarity, Offer, OfferStatus, Quote, RateSource,
+    Sale, SaleFees, SalesVolume, NFT,
+};
+
+/// Version of the persisted layout, bumped whenever a migration is added
+const STATE_VERSION: u16 = 3;
+
+/// Rate oracle settings of markets created before the oracle
+const DEFAULT_RATE_EPOCH_MS: u64 = 86_400_000;
+const DEFAULT_MAX_RATE_CHANGE_PERCENT: u8 = 10;
+const DEFAULT_RATE_SMOOTHING_PERCENT: u8 = 20;
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
+#[graphql(complex)]
 pub struct Market {
     pub publisher_collections: MapView<Owner, Vec<u64>>,
     /// owner, collection_id, token_id
     pub _assets: MapView<Owner, HashMap<u64, Vec<u16>>>,
     // pub token_owners: MapView<u16, HashMap<u64, Owner>>,
     // pub token_publishers: MapView<u16, HashMap<u64, Owner>>,
+    /// Deprecated: superseded by nft_owners, only read by the migration
     pub token_owners: MapView<u64, HashMap<u64, Owner>>,
     pub token_publishers: MapView<u64, HashMap<u64, Owner>>,
     pub credits_per_linera: RegisterView<Amount>,
     pub collection_id: RegisterView<u64>,
-    /// Linera token balance
-    /// If user want to buy asset here, they should deposit balance firstly, then buy
-    /// They balance could be withdrawed
     pub token_ids: MapView<u64, u16>,
     pub _collections: MapView<u64, Collection>,
     pub collection_uris: RegisterView<Vec<String>>,
     pub max_credits_percent: RegisterView<u8>,
     pub trade_fee_percent: RegisterView<u8>,
     pub avatars: MapView<Owner, Vec<u64>>,
+    /// Current owner of each token
+    pub nft_owners: MapView<NFTKey, Owner>,
+    pub state_version: RegisterView<u16>,
+    pub auction_id: RegisterView<u64>,
+    pub auctions: MapView<u64, Auction>,
+    /// Live auction of each token
+    pub nft_auctions: MapView<NFTKey, u64>,
+    pub offer_id: RegisterView<u64>,
+    pub offers: MapView<u64, Offer>,
+    /// Open offers of each token
+    pub nft_offers: MapView<NFTKey, Vec<u64>>,
+    pub max_royalty_percent: RegisterView<u8>,
+    /// Royalties publishers earned from secondary sales
+    pub royalty_earnings: MapView<Owner, Amount>,
+    pub sale_id: RegisterView<u64>,
+    pub sales: MapView<u64, Sale>,
+    pub token_sales: MapView<NFTKey, Vec<u64>>,
+    pub collection_sales: MapView<u64, Vec<u64>>,
+    /// Tokens minted by each owner in the collection
+    pub collection_mints: MapView<u64, HashMap<Owner, u16>>,
+    pub bundle_id: RegisterView<u64>,
+    pub bundles: MapView<u64, Bundle>,
+    /// Listed bundle of each token
+    pub nft_bundles: MapView<NFTKey, u64>,
+    /// Units held by each owner of edition tokens
+    pub edition_balances: MapView<NFTKey, HashMap<Owner, u16>>,
+    pub edition_listings: MapView<NFTKey, HashMap<Owner, EditionListing>>,
+    /// Linera token balance
+    /// If user want to buy asset here, they should deposit balance firstly, then buy
+    /// They balance could be withdrawed
+    pub balances: MapView<Owner, Amount>,
+    /// Creator of the application or whoever claimed it with SetAdmin, allowed to update
+    /// market settings
+    pub admin: RegisterView<Option<Owner>>,
+    pub fee_schedule: RegisterView<FeeSchedule>,
+    pub rate_epoch_ms: RegisterView<u64>,
+    pub max_rate_change_percent: RegisterView<u8>,
+    pub rate_smoothing_percent: RegisterView<u8>,
+    pub oracle_updated_at: RegisterView<Option<Timestamp>>,
+    pub rate_id: RegisterView<u64>,
+    /// Every credits_per_linera update by oracle or admin
+    pub rates: MapView<u64, CreditsPerLineraRate>,
+}
+
+#[ComplexObject]
+impl Market {
+    /// Tokens held by owner, ordered by collection then token id
+    async fn owned_nfts(
+        &self,
+        owner: Owner,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<NFTKey>> {
+        let mut keys = Vec::new();
+        if let Some(collections) = self._assets.get(&owner).await? {
+            for (collection_id, token_ids) in collections {
+                for token_id in token_ids {
+                    keys.push(NFTKey {
+                        collection_id,
+                        token_id,
+                    });
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Auctions not settled yet, ending soonest first
+    async fn live_auctions(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<Auction>> {
+        let mut auctions = Vec::new();
+        for auction_id in self.auctions.indices().await? {
+            if let Some(auction) = self.auctions.get(&auction_id).await? {
+                if auction.status == AuctionStatus::Live {
+                    auctions.push(auction);
+                }
+            }
+        }
+        auctions.sort_by(|a, b| a.end_at.cmp(&b.end_at));
+        Ok(auctions
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Bid history of an auction, latest first
+    async fn auction_bids(&self, auction_id: u64) -> async_graphql::Result<Vec<AuctionBid>> {
+        match self.auctions.get(&auction_id).await? {
+            Some(auction) => Ok(auction.bids.into_iter().rev().collect()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Cost of buying the token with credits at its current price
+    async fn quote(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+        credits: Amount,
+    ) -> async_graphql::Result<Quote> {
+        let seller = self.nft_owner(collection_id, token_id).await?;
+        let price = self.nft_price(collection_id, token_id).await?;
+        let fees = self.trading_fee(collection_id, price).await?;
+        let royalty = self
+            .royalty(collection_id, seller, price)
+            .await?
+            .map_or(Amount::ZERO, |(_, royalty)| royalty);
+        Ok(self.quote_sale(price, credits, fees, royalty).await?)
+    }
+
+    /// Updates of credits_per_linera, latest first
+    async fn credits_per_linera_history(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<CreditsPerLineraRate>> {
+        let mut rates = Vec::new();
+        for rate_id in self.rates.indices().await? {
+            if let Some(rate) = self.rates.get(&rate_id).await? {
+                rates.push(rate);
+            }
+        }
+        Ok(rates
+            .into_iter()
+            .rev()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Linera token deposited to market by owner
+    async fn balance(&self, owner: Owner) -> async_graphql::Result<Amount> {
+        Ok(self.balances.get(&owner).await?.unwrap_or_default())
+    }
+
+    /// Holders of an edition token, largest holding first
+    async fn edition_balances(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> async_graphql::Result<Vec<EditionBalance>> {
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut balances = self
+            .edition_balances
+            .get(&key)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(owner, units)| EditionBalance { owner, units })
+            .collect::<Vec<_>>();
+        balances.sort_by(|a, b| b.units.cmp(&a.units));
+        Ok(balances)
+    }
+
+    /// Edition units on sale, cheapest first
+    async fn edition_listings(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> async_graphql::Result<Vec<EditionListing>> {
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut listings = self
+            .edition_listings
+            .get(&key)
+            .await?
+            .unwrap_or_default()
+            .into_values()
+            .collect::<Vec<_>>();
+        listings.sort_by(|a, b| a.price.cmp(&b.price));
+        Ok(listings)
+    }
+
+    /// Bundles still listed, latest first
+    async fn listed_bundles(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<Bundle>> {
+        let mut bundles = Vec::new();
+        for bundle_id in self.bundles.indices().await? {
+            if let Some(bundle) = self.bundles.get(&bundle_id).await? {
+                if bundle.status == BundleStatus::Listed {
+                    bundles.push(bundle);
+                }
+            }
+        }
+        bundles.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(bundles
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Highest open offer of the token which is not expired yet
+    async fn best_offer(
+        &self,
+        ctx: &Context<'_>,
+        collection_id: u64,
+        token_id: u16,
+    ) -> async_graphql::Result<Option<Offer>> {
+        let now = *ctx.data::<Timestamp>()?;
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut best: Option<Offer> = None;
+        for offer_id in self.nft_offers.get(&key).await?.unwrap_or_default() {
+            if let Some(offer) = self.offers.get(&offer_id).await? {
+                if offer.expires_at <= now {
+                    continue;
+                }
+                if best.as_ref().map_or(true, |best| offer.value > best.value) {
+                    best = Some(offer);
+                }
+            }
+        }
+        Ok(best)
+    }
+
+    /// Metadata document of the token for wallets and indexers
+    async fn nft_metadata(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> async_graphql::Result<Option<NFTMetadataDocument>> {
+        Ok(self.nft_metadata_document(collection_id, token_id).await?)
+    }
+
+    /// Metadata document of the collection for wallets and indexers
+    async fn collection_metadata(
+        &self,
+        collection_id: u64,
+    ) -> async_graphql::Result<Option<CollectionMetadataDocument>> {
+        let Some(collection) = self._collections.get(&collection_id).await? else {
+            return Ok(None);
+        };
+        let image = match collection.metadata.cover_image.clone() {
+            Some(image) => image,
+            _ => collection.uri(0).unwrap_or_default(),
+        };
+        Ok(Some(CollectionMetadataDocument {
+            name: collection.name,
+            description: collection.metadata.description.unwrap_or_default(),
+            image,
+            external_link: collection.metadata.external_link,
+            seller_fee_basis_points: collection.royalty_percent as u16 * 100,
+            fee_recipient: collection.publisher,
+            collection_id,
+        }))
+    }
+
+    /// Tokens of the collection having the trait
+    async fn nfts_by_attribute(
+        &self,
+        collection_id: u64,
+        key: String,
+        value: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<NFT>> {
+        let Some(collection) = self._collections.get(&collection_id).await? else {
+            return Ok(Vec::new());
+        };
+        let mut nfts = collection
+            .nfts
+            .into_values()
+            .filter(|nft| {
+                nft.attributes
+                    .iter()
+                    .any(|attribute| attribute.key == key && attribute.value == value)
+            })
+            .collect::<Vec<_>>();
+        nfts.sort_by_key(|nft| nft.token_id);
+        Ok(nfts
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Rarity of tokens in the collection, rarest first
+    async fn collection_rarity(
+        &self,
+        collection_id: u64,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<NFTRarity>> {
+        let Some(collection) = self._collections.get(&collection_id).await? else {
+            return Ok(Vec::new());
+        };
+        let total = collection.nfts.len() as u64;
+        let mut trait_counts = HashMap::<(&str, &str), u64>::new();
+        for nft in collection.nfts.values() {
+            for attribute in nft.attributes.iter() {
+                *trait_counts
+                    .entry((attribute.key.as_str(), attribute.value.as_str()))
+                    .or_default() += 1;
+            }
+        }
+        let mut rarities = collection
+            .nfts
+            .values()
+            .map(|nft| NFTRarity {
+                token_id: nft.token_id,
+                score: nft
+                    .attributes
+                    .iter()
+                    .map(|attribute| {
+                        total * 100
+                            / trait_counts[&(attribute.key.as_str(), attribute.value.as_str())]
+                    })
+                    .sum(),
+                rank: 0,
+            })
+            .collect::<Vec<_>>();
+        rarities.sort_by(|a, b| b.score.cmp(&a.score).then(a.token_id.cmp(&b.token_id)));
+        for (index, rarity) in rarities.iter_mut().enumerate() {
+            rarity.rank = index as u32 + 1;
+        }
+        Ok(rarities
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Sales of the token, latest first
+    async fn token_sales(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<Sale>> {
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut sales = Vec::new();
+        for sale_id in self
+            .token_sales
+            .get(&key)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+        {
+            if let Some(sale) = self.sales.get(&sale_id).await? {
+                sales.push(sale);
+            }
+        }
+        Ok(sales)
+    }
+
+    async fn last_sale_price(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> async_graphql::Result<Option<Amount>> {
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let Some(sale_id) = self
+            .token_sales
+            .get(&key)
+            .await?
+            .and_then(|sale_ids| sale_ids.last().copied())
+        else {
+            return Ok(None);
+        };
+        Ok(self.sales.get(&sale_id).await?.map(|sale| sale.price))
+    }
+
+    /// Lowest price of the tokens on sale in the collection
+    async fn floor_price(&self, collection_id: u64) -> async_graphql::Result<Option<Amount>> {
+        let Some(collection) = self._collections.get(&collection_id).await? else {
+            return Ok(None);
+        };
+        let key = |token_id: u16| NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut floor: Option<Amount> = None;
+        for nft in collection.nfts.values() {
+            if !nft.on_sale || self.nft_auctions.contains_key(&key(nft.token_id)).await? {
+                continue;
+            }
+            if let Some(price) = nft.price.or(collection.price) {
+                floor = Some(floor.map_or(price, |floor| floor.min(price)));
+            }
+        }
+        Ok(floor)
+    }
+
+    /// Sales in [since, until) of the collection, or of the whole market without collection
+    async fn sales_volume(
+        &self,
+        collection_id: Option<u64>,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+    ) -> async_graphql::Result<SalesVolume> {
+        let sale_ids = match collection_id {
+            Some(collection_id) => self
+                .collection_sales
+                .get(&collection_id)
+                .await?
+                .unwrap_or_default(),
+            _ => self.sales.indices().await?,
+        };
+        let mut volume = SalesVolume {
+            sales: 0,
+            volume: Amount::ZERO,
+        };
+        for sale_id in sale_ids {
+            let Some(sale) = self.sales.get(&sale_id).await? else {
+                continue;
+            };
+            if since.is_some_and(|since| sale.created_at < since)
+                || until.is_some_and(|until| sale.created_at >= until)
+            {
+                continue;
+            }
+            volume.sales += 1;
+            volume.volume = volume.volume.saturating_add(sale.price);
+        }
+        Ok(volume)
+    }
 }
 
 #[allow(dead_code)]
@@ -37,6 +496,116 @@ impl Market {
         self.collection_id.set(state.collection_id.unwrap_or(1000));
         self.max_credits_percent.set(state.max_credits_percent);
         self.trade_fee_percent.set(state.trade_fee_percent);
+        self.max_royalty_percent.set(state.max_royalty_percent);
+        self.fee_schedule
+            .set(Self::trade_fee_schedule(state.trade_fee_percent));
+        self.rate_epoch_ms.set(state.rate_epoch_ms);
+        self.max_rate_change_percent
+            .set(state.max_rate_change_percent);
+        self.rate_smoothing_percent
+            .set(state.rate_smoothing_percent);
+        self.state_version.set(STATE_VERSION);
+    }
+
+    /// Whole fee deposited to foundation, as trade_fee_percent did before fee schedule
+    fn trade_fee_schedule(trade_fee_percent: u8) -> FeeSchedule {
+        FeeSchedule {
+            default_rate: FeeRate {
+                foundation_percent: trade_fee_percent,
+                ..FeeRate::default()
+            },
+            ..FeeSchedule::default()
+        }
+    }
+
+    pub(crate) async fn migrate(&mut self) -> Result<(), MarketError> {
+        let version = *self.state_version.get();
+        if version >= STATE_VERSION {
+            return Ok(());
+        }
+        if version < 1 {
+            self.migrate_nft_owners().await?;
+        }
+        if version < 2 {
+            self.fee_schedule
+                .set(Self::trade_fee_schedule(*self.trade_fee_percent.get()));
+        }
+        if version < 3 {
+            self.rate_epoch_ms.set(DEFAULT_RATE_EPOCH_MS);
+            self.max_rate_change_percent
+                .set(DEFAULT_MAX_RATE_CHANGE_PERCENT);
+            self.rate_smoothing_percent
+                .set(DEFAULT_RATE_SMOOTHING_PERCENT);
+        }
+        self.state_version.set(STATE_VERSION);
+        Ok(())
+    }
+
+    /// Rebuild the ownership index and per-owner assets from token_owners written
+    /// by earlier versions, where buyers were never added to their own assets
+    async fn migrate_nft_owners(&mut self) -> Result<(), MarketError> {
+        let mut owners = Vec::new();
+        for token_id in self.token_owners.indices().await? {
+            if let Some(collection_owners) = self.token_owners.get(&token_id).await? {
+                for (collection_id, owner) in collection_owners {
+                    owners.push((
+                        NFTKey {
+                            collection_id,
+                            token_id: token_id as u16,
+                        },
+                        owner,
+                    ));
+                }
+            }
+        }
+        self._assets.clear();
+        for (key, owner) in owners {
+            self.nft_owners.insert(&key, owner)?;
+            self.add_asset(owner, key).await?;
+        }
+        self.token_owners.clear();
+        Ok(())
+    }
+
+    async fn add_asset(&mut self, owner: Owner, key: NFTKey) -> Result<(), MarketError> {
+        let mut collections = self._assets.get(&owner).await?.unwrap_or_default();
+        let token_ids = collections.entry(key.collection_id).or_default();
+        if !token_ids.contains(&key.token_id) {
+            token_ids.push(key.token_id);
+        }
+        self._assets.insert(&owner, collections)?;
+        Ok(())
+    }
+
+    async fn remove_asset(&mut self, owner: Owner, key: NFTKey) -> Result<(), MarketError> {
+        let Some(mut collections) = self._assets.get(&owner).await? else {
+            return Ok(());
+        };
+        if let Some(token_ids) = collections.get_mut(&key.collection_id) {
+            token_ids.retain(|token_id| *token_id != key.token_id);
+            if token_ids.is_empty() {
+                collections.remove(&key.collection_id);
+            }
+        }
+        if collections.is_empty() {
+            self._assets.remove(&owner)?;
+        } else {
+            self._assets.insert(&owner, collections)?;
+        }
+        Ok(())
+    }
+
+    /// Move a token to a new owner, keeping the index and both owners' assets in step
+    pub(crate) async fn transfer_nft_ownership(
+        &mut self,
+        key: NFTKey,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        if let Some(from) = self.nft_owners.get(&key).await? {
+            self.remove_asset(from, key).await?;
+        }
+        self.nft_owners.insert(&key, to)?;
+        self.add_asset(to, key).await
     }
 
     pub(crate) async fn initial_state(&self) -> Result<InstantiationArgument, MarketError> {
@@ -45,6 +614,10 @@ impl Market {
             max_credits_percent: *self.max_credits_percent.get(),
             trade_fee_percent: *self.trade_fee_percent.get(),
             collection_id: Some(*self.collection_id.get()),
+            max_royalty_percent: *self.max_royalty_percent.get(),
+            rate_epoch_ms: *self.rate_epoch_ms.get(),
+            max_rate_change_percent: *self.max_rate_change_percent.get(),
+            rate_smoothing_percent: *self.rate_smoothing_percent.get(),
         })
     }
 
@@ -60,6 +633,7 @@ impl Market {
         self._assets.get(&owner).await.unwrap().unwrap()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create_collection(
         &mut self,
         owner: Owner,
@@ -67,11 +641,18 @@ impl Market {
         price: Option<Amount>,
         name: String,
         uris: Vec<String>,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
         now: Timestamp,
     ) -> Result<(), MarketError> {
         if self.collection_uris.get().contains(&base_uri) {
             return Err(MarketError::BaseURIALreadyExists);
         }
+        if !metadata.is_valid() {
+            return Err(MarketError::InvalidMetadata);
+        }
+        // Collections come from approved assets, so cap the royalty instead of failing here
+        let royalty_percent = royalty_percent.min(*self.max_royalty_percent.get());
         let collection_id = *self.collection_id.get();
         let collection = Collection {
             collection_id,
@@ -82,6 +663,8 @@ impl Market {
             nfts: HashMap::new(),
             created_at: now,
             publisher: owner,
+            royalty_percent,
+            metadata,
         };
         match self.publisher_collections.get(&owner).await {
             Ok(Some(mut collections)) => {
@@ -118,6 +701,7 @@ impl Market {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn mint_nft(
         &mut self,
         owner: Owner,
@@ -125,63 +709,198 @@ impl Market {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        attributes: Vec<NFTAttribute>,
+        editions: u16,
         now: Timestamp,
     ) -> Result<(), MarketError> {
         self.validate_collection_owner(collection_id, owner).await?;
-        match self._collections.get(&collection_id).await {
-            Ok(Some(mut collection)) => {
-                if uri_index >= collection.uris.len() as u16 {
-                    return Err(MarketError::InvalidUriIndex);
-                }
-                if collection.price.is_none() && price.is_none() {
-                    return Err(MarketError::InvalidPrice);
-                }
-                match self.token_ids.get(&collection_id).await {
-                    Ok(Some(token_id)) => {
-                        collection.nfts.insert(
-                            token_id,
-                            NFT {
-                                token_id,
-                                uri_index,
-                                price,
-                                on_sale: true,
-                                minted_at: now,
-                                name,
-                            },
-                        );
-                        self._collections.insert(&collection_id, collection)?;
-                        self.token_ids.insert(&collection_id, token_id + 1)?;
-                        let token_id = token_id as u64;
-                        match self.token_owners.get(&token_id).await {
-                            Ok(Some(mut collection_owners)) => {
-                                collection_owners.insert(collection_id, owner);
-                                self.token_owners.insert(&token_id, collection_owners)?;
-                            }
-                            _ => {
-                                let mut collection_owners = HashMap::new();
-                                collection_owners.insert(collection_id, owner);
-                                self.token_owners.insert(&token_id, collection_owners)?;
-                            }
-                        }
-                        match self.token_publishers.get(&token_id).await {
-                            Ok(Some(mut collection_publisher)) => {
-                                collection_publisher.insert(collection_id, owner);
-                                self.token_publishers
-                                    .insert(&token_id, collection_publisher)?;
-                            }
-                            _ => {
-                                let mut collection_publisher = HashMap::new();
-                                collection_publisher.insert(collection_id, owner);
-                                self.token_publishers
-                                    .insert(&token_id, collection_publisher)?;
-                            }
-                        }
-                    }
-                    _ => return Err(MarketError::TokenIDNotExists),
-                }
-            }
+        if editions == 0 {
+            return Err(MarketError::InvalidEditions);
+        }
+        let mut keys = attributes
+            .iter()
+            .map(|attribute| attribute.key.as_str())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        if keys.len() != attributes.len() || keys.iter().any(|key| key.is_empty()) {
+            return Err(MarketError::InvalidAttributes);
+        }
+        let collection = match self._collections.get(&collection_id).await? {
+            Some(collection) => collection,
+            _ => return Err(MarketError::CollectionNotExists),
+        };
+        // Public mint takes the uris in order, the publisher could not take one out of turn
+        if collection.metadata.is_public_mint() {
+            return Err(MarketError::PublicMintOnly);
+        }
+        if uri_index >= collection.uris.len() as u16 {
+            return Err(MarketError::InvalidUriIndex);
+        }
+        if collection.price.is_none() && price.is_none() {
+            return Err(MarketError::InvalidPrice);
+        }
+        self.validate_mint(&collection, owner, now).await?;
+        self._mint_nft(
+            collection, owner, uri_index, price, name, attributes, editions, true, now,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Mint the next uri of a public mint collection to the minter, returns minted token
+    /// id, price paid and the publisher to be paid
+    pub(crate) async fn public_mint_nft(
+        &mut self,
+        minter: Owner,
+        collection_id: u64,
+        now: Timestamp,
+    ) -> Result<(u16, Amount, Owner), MarketError> {
+        let collection = match self._collections.get(&collection_id).await? {
+            Some(collection) => collection,
             _ => return Err(MarketError::CollectionNotExists),
         };
+        if !collection.metadata.is_public_mint() {
+            return Err(MarketError::PublicMintNotEnabled);
+        }
+        let Some(price) = collection.price else {
+            return Err(MarketError::InvalidPrice);
+        };
+        let uri_index = collection.nfts.len() as u16;
+        if uri_index >= collection.uris.len() as u16 {
+            return Err(MarketError::MaxSupplyReached);
+        }
+        self.validate_mint(&collection, minter, now).await?;
+        let publisher = collection.publisher;
+        let name = format!("{} #{}", collection.name, uri_index);
+        let token_id = self
+            ._mint_nft(
+                collection,
+                minter,
+                uri_index,
+                None,
+                name,
+                Vec::new(),
+                1,
+                false,
+                now,
+            )
+            .await?;
+        Ok((token_id, price, publisher))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn _mint_nft(
+        &mut self,
+        mut collection: Collection,
+        owner: Owner,
+        uri_index: u16,
+        price: Option<Amount>,
+        name: String,
+        attributes: Vec<NFTAttribute>,
+        editions: u16,
+        on_sale: bool,
+        now: Timestamp,
+    ) -> Result<u16, MarketError> {
+        let collection_id = collection.collection_id;
+        let publisher = collection.publisher;
+        let token_id = match self.token_ids.get(&collection_id).await? {
+            Some(token_id) => token_id,
+            _ => return Err(MarketError::TokenIDNotExists),
+        };
+        let nft = NFT {
+            token_id,
+            uri_index,
+            price,
+            on_sale: on_sale && editions == 1,
+            minted_at: now,
+            name,
+            attributes,
+            editions,
+        };
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        if nft.is_edition() {
+            // Minter holds every unit, listed at the token price when minted on sale
+            self.edition_balances
+                .insert(&key, HashMap::from([(owner, editions)]))?;
+            self.add_asset(owner, key).await?;
+            if let Some(price) = price.or(collection.price).filter(|_| on_sale) {
+                self.edition_listings.insert(
+                    &key,
+                    HashMap::from([(
+                        owner,
+                        EditionListing {
+                            seller: owner,
+                            units: editions,
+                            price,
+                        },
+                    )]),
+                )?;
+            }
+        } else {
+            self.transfer_nft_ownership(key, owner).await?;
+        }
+        collection.nfts.insert(token_id, nft);
+        self._collections.insert(&collection_id, collection)?;
+        self.token_ids.insert(&collection_id, token_id + 1)?;
+        let mut mints = self
+            .collection_mints
+            .get(&collection_id)
+            .await?
+            .unwrap_or_default();
+        *mints.entry(owner).or_default() += 1;
+        self.collection_mints.insert(&collection_id, mints)?;
+        let _token_id = token_id as u64;
+        match self.token_publishers.get(&_token_id).await? {
+            Some(mut collection_publisher) => {
+                collection_publisher.insert(collection_id, publisher);
+                self.token_publishers
+                    .insert(&_token_id, collection_publisher)?;
+            }
+            _ => {
+                let mut collection_publisher = HashMap::new();
+                collection_publisher.insert(collection_id, publisher);
+                self.token_publishers
+                    .insert(&_token_id, collection_publisher)?;
+            }
+        }
+        Ok(token_id)
+    }
+
+    async fn validate_mint(
+        &self,
+        collection: &Collection,
+        owner: Owner,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        let metadata = &collection.metadata;
+        if metadata
+            .mint_start_at
+            .is_some_and(|start_at| now < start_at)
+            || metadata.mint_end_at.is_some_and(|end_at| now >= end_at)
+        {
+            return Err(MarketError::MintWindowClosed);
+        }
+        if metadata
+            .max_supply
+            .is_some_and(|max_supply| collection.nfts.len() >= max_supply as usize)
+        {
+            return Err(MarketError::MaxSupplyReached);
+        }
+        if let Some(mint_limit) = metadata.mint_limit_per_owner {
+            let minted = self
+                .collection_mints
+                .get(&collection.collection_id)
+                .await?
+                .and_then(|mints| mints.get(&owner).copied())
+                .unwrap_or_default();
+            if minted >= mint_limit {
+                return Err(MarketError::MintLimitReached);
+            }
+        }
         Ok(())
     }
 
@@ -197,47 +916,20 @@ impl Market {
                     if !nft.on_sale {
                         return Err(MarketError::TokenNotOnSale);
                     }
-                    let token_id = token_id as u64;
-                    let token_owners = match self.token_owners.get(&token_id).await {
-                        Ok(Some(owners)) => owners,
-                        _ => HashMap::default(),
+                    let key = NFTKey {
+                        collection_id,
+                        token_id,
                     };
-                    let owner = match token_owners.get(&collection_id) {
-                        Some(owner) => *owner,
+                    let owner = match self.nft_owners.get(&key).await? {
+                        Some(owner) => owner,
                         _ => return Err(MarketError::CollectionNotExists),
                     };
                     if owner == buyer {
                         return Err(MarketError::BuyerIsOwner);
                     }
-                    let mut token_owners = token_owners.clone();
-                    token_owners.insert(collection_id, buyer);
-                    self.token_owners.insert(&token_id, token_owners)?;
-                    match self._assets.get(&owner).await {
-                        Ok(Some(collections)) => {
-                            let collections = collections.clone();
-                            let token_id = token_id as u16;
-                            match collections.get(&collection_id) {
-                                Some(token_ids) => {
-                                    let mut token_ids = token_ids.clone();
-                                    token_ids.push(token_id);
-                                    let mut collections = collections.clone();
-                                    collections.insert(collection_id, token_ids);
-                                    self._assets.insert(&owner, collections)?;
-                                }
-                                None => {
-                                    let mut collections = collections.clone();
-                                    collections.insert(collection_id, vec![token_id]);
-                                    self._assets.insert(&owner, collections)?;
-                                }
-                            }
-                        }
-                        _ => {
-                            let mut collections = HashMap::default();
-                            let token_id = token_id as u16;
-                            collections.insert(collection_id, vec![token_id]);
-                            self._assets.insert(&owner, collections)?;
-                        }
-                    }
+                    self.take_off_sale(key).await?;
+                    self.clear_avatar(owner, key).await?;
+                    self.transfer_nft_ownership(key, buyer).await?;
                 }
                 _ => return Err(MarketError::TokenIDNotExists),
             },
@@ -251,13 +943,15 @@ impl Market {
         collection_id: u64,
         token_id: u16,
     ) -> Result<Owner, MarketError> {
-        let token_id = token_id as u64;
-        let token_owners = match self.token_owners.get(&token_id).await {
-            Ok(Some(owners)) => owners,
-            _ => HashMap::default(),
-        };
-        match token_owners.get(&collection_id) {
-            Some(owner) => Ok(*owner),
+        match self
+            .nft_owners
+            .get(&NFTKey {
+                collection_id,
+                token_id,
+            })
+            .await?
+        {
+            Some(owner) => Ok(owner),
             _ => Err(MarketError::NotCollectionOwner),
         }
     }
@@ -336,6 +1030,11 @@ impl Market {
         if self.nft_owner(collection_id, token_id).await.unwrap() != owner {
             return Err(MarketError::NotTokenOwner);
         }
+        self.validate_not_locked(NFTKey {
+            collection_id,
+            token_id,
+        })
+        .await?;
         match self._collections.get(&collection_id).await {
             Ok(Some(mut collection)) => match collection.nfts.get(&token_id) {
                 Some(nft) => {
@@ -381,9 +1080,13 @@ impl Market {
         collection_id: u64,
         token_id: u16,
     ) -> Result<(), MarketError> {
-        match self.nft_owner(collection_id, token_id).await {
-            Ok(_owner) => {
-                if _owner != owner {
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        match self.holds_nft(owner, key).await {
+            Ok(held) => {
+                if !held {
                     Err(MarketError::NotTokenOwner)
                 } else {
                     match self
@@ -399,12 +1102,976 @@ impl Market {
         }
     }
 
-    pub(crate) async fn trading_fee(&self, amount: Amount) -> Result<Amount, MarketError> {
-        Ok(Amount::from_attos(
-            Amount::from_attos(*self.trade_fee_percent.get() as u128)
-                .saturating_mul(amount.into())
-                .saturating_div(Amount::from_attos(100 as u128)),
-        ))
+    /// Owner holds the unique token or at least one unit of an edition token
+    async fn holds_nft(&self, owner: Owner, key: NFTKey) -> Result<bool, MarketError> {
+        if self.nft_owners.get(&key).await? == Some(owner) {
+            return Ok(true);
+        }
+        Ok(self.edition_balance(key, owner).await? > 0)
+    }
+
+    pub(crate) async fn edition_balance(
+        &self,
+        key: NFTKey,
+        owner: Owner,
+    ) -> Result<u16, MarketError> {
+        Ok(self
+            .edition_balances
+            .get(&key)
+            .await?
+            .and_then(|balances| balances.get(&owner).copied())
+            .unwrap_or_default())
+    }
+
+    async fn validate_edition(&self, key: NFTKey) -> Result<(), MarketError> {
+        match self._collections.get(&key.collection_id).await? {
+            Some(collection) => match collection.nfts.get(&key.token_id) {
+                Some(nft) if nft.is_edition() => Ok(()),
+                Some(_) => Err(MarketError::NFTNotEdition),
+                _ => Err(MarketError::TokenIDNotExists),
+            },
+            _ => Err(MarketError::CollectionNotExists),
+        }
+    }
+
+    /// Set the units the holder offers at a unit price, zero units takes the listing down
+    pub(crate) async fn list_editions(
+        &mut self,
+        seller: Owner,
+        key: NFTKey,
+        units: u16,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        self.validate_edition(key).await?;
+        if units > self.edition_balance(key, seller).await? {
+            return Err(MarketError::InsufficientEditions);
+        }
+        if units > 0 && price == Amount::ZERO {
+            return Err(MarketError::InvalidPrice);
+        }
+        let mut listings = self.edition_listings.get(&key).await?.unwrap_or_default();
+        if units == 0 {
+            listings.remove(&seller);
+        } else {
+            listings.insert(
+                seller,
+                EditionListing {
+                    seller,
+                    units,
+                    price,
+                },
+            );
+        }
+        if listings.is_empty() {
+            self.edition_listings.remove(&key)?;
+        } else {
+            self.edition_listings.insert(&key, listings)?;
+        }
+        Ok(())
+    }
+
+    /// Move units between holders, the sender's listing never exceeds what is left
+    /// and the token stops being the sender's asset and avatar once all units are gone
+    async fn move_editions(
+        &mut self,
+        key: NFTKey,
+        from: Owner,
+        to: Owner,
+        units: u16,
+    ) -> Result<(), MarketError> {
+        self.validate_edition(key).await?;
+        if units == 0 {
+            return Err(MarketError::InvalidEditions);
+        }
+        let mut balances = self.edition_balances.get(&key).await?.unwrap_or_default();
+        let balance = balances.get(&from).copied().unwrap_or_default();
+        if balance < units {
+            return Err(MarketError::InsufficientEditions);
+        }
+        let left = balance - units;
+        if left == 0 {
+            balances.remove(&from);
+            self.remove_asset(from, key).await?;
+            self.clear_avatar(from, key).await?;
+        } else {
+            balances.insert(from, left);
+        }
+        *balances.entry(to).or_default() += units;
+        self.edition_balances.insert(&key, balances)?;
+        self.add_asset(to, key).await?;
+
+        let mut listings = self.edition_listings.get(&key).await?.unwrap_or_default();
+        if let Some(listing) = listings.get_mut(&from) {
+            listing.units = listing.units.min(left);
+            if listing.units == 0 {
+                listings.remove(&from);
+            }
+        }
+        if listings.is_empty() {
+            self.edition_listings.remove(&key)?;
+        } else {
+            self.edition_listings.insert(&key, listings)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn transfer_editions(
+        &mut self,
+        owner: Owner,
+        key: NFTKey,
+        to: Owner,
+        units: u16,
+    ) -> Result<(), MarketError> {
+        if to == owner {
+            return Err(MarketError::ReceiverIsOwner);
+        }
+        self.move_editions(key, owner, to, units).await
+    }
+
+    /// Buy units from one listing, returns the total price to be paid
+    pub(crate) async fn buy_editions(
+        &mut self,
+        buyer: Owner,
+        key: NFTKey,
+        seller: Owner,
+        units: u16,
+    ) -> Result<Amount, MarketError> {
+        if buyer == seller {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        let mut listings = self.edition_listings.get(&key).await?.unwrap_or_default();
+        let Some(listing) = listings.get_mut(&seller) else {
+            return Err(MarketError::TokenNotOnSale);
+        };
+        if units == 0 || units > listing.units {
+            return Err(MarketError::InsufficientEditions);
+        }
+        let price = listing.price.saturating_mul(units as u128);
+        listing.units -= units;
+        if listing.units == 0 {
+            listings.remove(&seller);
+        }
+        if listings.is_empty() {
+            self.edition_listings.remove(&key)?;
+        } else {
+            self.edition_listings.insert(&key, listings)?;
+        }
+        self.move_editions(key, seller, buyer, units).await?;
+        Ok(price)
+    }
+
+    /// Hand a token to another owner, taking it off sale and dropping it as the sender's avatar
+    pub(crate) async fn transfer_nft(
+        &mut self,
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        if self.nft_owner(collection_id, token_id).await? != owner {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if to == owner {
+            return Err(MarketError::ReceiverIsOwner);
+        }
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        self.validate_not_locked(key).await?;
+        self.take_off_sale(key).await?;
+        self.clear_avatar(owner, key).await?;
+        self.transfer_nft_ownership(key, to).await
+    }
+
+    async fn take_off_sale(&mut self, key: NFTKey) -> Result<(), MarketError> {
+        match self._collections.get(&key.collection_id).await? {
+            Some(mut collection) => match collection.nfts.get_mut(&key.token_id) {
+                Some(nft) => {
+                    nft.on_sale = false;
+                    self._collections.insert(&key.collection_id, collection)?;
+                }
+                _ => return Err(MarketError::TokenIDNotExists),
+            },
+            _ => return Err(MarketError::CollectionNotExists),
+        }
+        Ok(())
+    }
+
+    async fn clear_avatar(&mut self, owner: Owner, key: NFTKey) -> Result<(), MarketError> {
+        if let Some(avatar) = self.avatars.get(&owner).await? {
+            if avatar == vec![key.collection_id, key.token_id as u64] {
+                self.avatars.remove(&owner)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Tokens in a live auction or a listed bundle could not change hands otherwise
+    async fn validate_not_locked(&self, key: NFTKey) -> Result<(), MarketError> {
+        if self.nft_auctions.contains_key(&key).await? {
+            return Err(MarketError::NFTInAuction);
+        }
+        if self.nft_bundles.contains_key(&key).await? {
+            return Err(MarketError::NFTInBundle);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn auction(&self, auction_id: u64) -> Result<Auction, MarketError> {
+        match self.auctions.get(&auction_id).await? {
+            Some(auction) => Ok(auction),
+            _ => Err(MarketError::AuctionNotExists),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn create_auction(
+        &mut self,
+        seller: Owner,
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        reserve: Amount,
+        start_price: Option<Amount>,
+        start_at: Timestamp,
+        end_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<u64, MarketError> {
+        if self.nft_owner(collection_id, token_id).await? != seller {
+            return Err(MarketError::NotTokenOwner);
+        }
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        self.validate_not_locked(key).await?;
+        if end_at <= start_at || end_at <= now {
+            return Err(MarketError::InvalidAuction);
+        }
+        let start_price = match kind {
+            AuctionKind::English => None,
+            AuctionKind::Dutch => match start_price {
+                Some(price) if price > reserve => Some(price),
+                _ => return Err(MarketError::InvalidAuction),
+            },
+        };
+        self.take_off_sale(key).await?;
+        let auction_id = *self.auction_id.get();
+        self.auctions.insert(
+            &auction_id,
+            Auction {
+                auction_id,
+                collection_id,
+                token_id,
+                seller,
+                kind,
+                reserve,
+                start_price,
+                start_at,
+                end_at,
+                bids: Vec::new(),
+                status: AuctionStatus::Live,
+                winner: None,
+                created_at: now,
+                settled_at: None,
+            },
+        )?;
+        self.nft_auctions.insert(&key, auction_id)?;
+        self.auction_id.set(auction_id + 1);
+        Ok(auction_id)
+    }
+
+    /// Lowest acceptable bid value at now
+    pub(crate) fn auction_price(&self, auction: &Auction, now: Timestamp) -> Amount {
+        match auction.kind {
+            AuctionKind::English => match auction.bids.last() {
+                Some(bid) => bid.value,
+                _ => auction.reserve,
+            },
+            AuctionKind::Dutch => {
+                let start_price = auction.start_price.unwrap_or(auction.reserve);
+                let duration = auction.end_at.delta_since(auction.start_at).as_micros();
+                let elapsed = now.delta_since(auction.start_at).as_micros().min(duration);
+                let drop = u128::from(start_price.saturating_sub(auction.reserve))
+                    .saturating_mul(elapsed as u128)
+                    .saturating_div(duration.max(1) as u128);
+                start_price.saturating_sub(Amount::from_attos(drop))
+            }
+        }
+    }
+
+    /// Returns the bid outbid by this one, which should be refunded
+    pub(crate) async fn place_bid(
+        &mut self,
+        bidder: Owner,
+        auction_id: u64,
+        amount: Amount,
+        credits: Amount,
+        now: Timestamp,
+    ) -> Result<Option<AuctionBid>, MarketError> {
+        let mut auction = self.auction(auction_id).await?;
+        if auction.status != AuctionStatus::Live || now < auction.start_at || now >= auction.end_at
+        {
+            return Err(MarketError::AuctionNotLive);
+        }
+        if bidder == auction.seller {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        let value = amount.saturating_add(self.credits_to_tokens(credits).await?);
+        if !self
+            .escrow_covers_charges(auction.collection_id, auction.seller, value, amount)
+            .await?
+        {
+            return Err(MarketError::InvalidBid);
+        }
+        let price = self.auction_price(&auction, now);
+        let outbid = match auction.kind {
+            AuctionKind::English => {
+                let outbid = auction.bids.last().cloned();
+                if value < price || (outbid.is_some() && value == price) {
+                    return Err(MarketError::InvalidBid);
+                }
+                outbid
+            }
+            AuctionKind::Dutch => {
+                if !auction.bids.is_empty() {
+                    return Err(MarketError::AuctionNotLive);
+                }
+                if value < price {
+                    return Err(MarketError::InvalidBid);
+                }
+                None
+            }
+        };
+        auction.bids.push(AuctionBid {
+            bidder,
+            amount,
+            credits,
+            value,
+            created_at: now,
+        });
+        self.auctions.insert(&auction_id, auction)?;
+        Ok(outbid)
+    }
+
+    /// Close the auction and hand the token to the highest bidder, if any
+    pub(crate) async fn settle_auction(
+        &mut self,
+        auction_id: u64,
+        now: Timestamp,
+    ) -> Result<Auction, MarketError> {
+        let mut auction = self.auction(auction_id).await?;
+        if auction.status != AuctionStatus::Live {
+            return Err(MarketError::AuctionNotLive);
+        }
+        let sold = auction.kind == AuctionKind::Dutch && !auction.bids.is_empty();
+        if !sold && now < auction.end_at {
+            return Err(MarketError::AuctionNotEnded);
+        }
+        let key = NFTKey {
+            collection_id: auction.collection_id,
+            token_id: auction.token_id,
+        };
+        if let Some(bid) = auction.bids.last() {
+            auction.winner = Some(bid.bidder);
+            self.clear_avatar(auction.seller, key).await?;
+            self.transfer_nft_ownership(key, bid.bidder).await?;
+        }
+        auction.status = AuctionStatus::Settled;
+        auction.settled_at = Some(now);
+        self.auctions.insert(&auction_id, auction.clone())?;
+        self.nft_auctions.remove(&key)?;
+        Ok(auction)
+    }
+
+    pub(crate) async fn deposit(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), MarketError> {
+        if amount == Amount::ZERO {
+            return Err(MarketError::InvalidAmount);
+        }
+        let balance = self.balances.get(&owner).await?.unwrap_or_default();
+        self.balances
+            .insert(&owner, balance.saturating_add(amount))?;
+        Ok(())
+    }
+
+    /// Give escrowed tokens back to owner's deposit, e.g. an outbid bid
+    pub(crate) async fn refund_balance(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), MarketError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        let balance = self.balances.get(&owner).await?.unwrap_or_default();
+        self.balances
+            .insert(&owner, balance.saturating_add(amount))?;
+        Ok(())
+    }
+
+    /// Take amount out of owner's deposit, for both withdraw and purchase
+    pub(crate) async fn spend_balance(
+        &mut self,
+        owner: Owner,
+        amount: Amount,
+    ) -> Result<(), MarketError> {
+        let balance = self.balances.get(&owner).await?.unwrap_or_default();
+        if balance < amount {
+            return Err(MarketError::InsufficientBalance);
+        }
+        let balance = balance.saturating_sub(amount);
+        if balance == Amount::ZERO {
+            self.balances.remove(&owner)?;
+        } else {
+            self.balances.insert(&owner, balance)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn bundle(&self, bundle_id: u64) -> Result<Bundle, MarketError> {
+        match self.bundles.get(&bundle_id).await? {
+            Some(bundle) => Ok(bundle),
+            _ => Err(MarketError::BundleNotExists),
+        }
+    }
+
+    pub(crate) async fn create_bundle(
+        &mut self,
+        seller: Owner,
+        nfts: Vec<NFTKey>,
+        price: Amount,
+        now: Timestamp,
+    ) -> Result<u64, MarketError> {
+        let mut keys = nfts.clone();
+        keys.sort();
+        keys.dedup();
+        if keys.len() < 2 || keys.len() != nfts.len() || price == Amount::ZERO {
+            return Err(MarketError::InvalidBundle);
+        }
+        for key in nfts.iter() {
+            if self.nft_owner(key.collection_id, key.token_id).await? != seller {
+                return Err(MarketError::NotTokenOwner);
+            }
+            self.validate_not_locked(*key).await?;
+        }
+        let bundle_id = *self.bundle_id.get();
+        for key in nfts.iter() {
+            self.take_off_sale(*key).await?;
+            self.nft_bundles.insert(key, bundle_id)?;
+        }
+        self.bundles.insert(
+            &bundle_id,
+            Bundle {
+                bundle_id,
+                seller,
+                nfts,
+                price,
+                status: BundleStatus::Listed,
+                buyer: None,
+                created_at: now,
+                sold_at: None,
+            },
+        )?;
+        self.bundle_id.set(bundle_id + 1);
+        Ok(bundle_id)
+    }
+
+    async fn close_bundle(&mut self, bundle: &Bundle) -> Result<(), MarketError> {
+        for key in bundle.nfts.iter() {
+            self.nft_bundles.remove(key)?;
+        }
+        self.bundles.insert(&bundle.bundle_id, bundle.clone())?;
+        Ok(())
+    }
+
+    pub(crate) async fn cancel_bundle(
+        &mut self,
+        seller: Owner,
+        bundle_id: u64,
+    ) -> Result<(), MarketError> {
+        let mut bundle = self.bundle(bundle_id).await?;
+        if bundle.seller != seller {
+            return Err(MarketError::InvalidOwner);
+        }
+        if bundle.status != BundleStatus::Listed {
+            return Err(MarketError::BundleNotListed);
+        }
+        bundle.status = BundleStatus::Cancelled;
+        self.close_bundle(&bundle).await
+    }
+
+    /// All tokens are validated before any of them moves so the bundle is sold as a whole
+    pub(crate) async fn buy_bundle(
+        &mut self,
+        buyer: Owner,
+        bundle_id: u64,
+        now: Timestamp,
+    ) -> Result<Bundle, MarketError> {
+        let mut bundle = self.bundle(bundle_id).await?;
+        if bundle.status != BundleStatus::Listed {
+            return Err(MarketError::BundleNotListed);
+        }
+        if bundle.seller == buyer {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        for key in bundle.nfts.iter() {
+            if self.nft_owner(key.collection_id, key.token_id).await? != bundle.seller {
+                return Err(MarketError::NotTokenOwner);
+            }
+        }
+        for key in bundle.nfts.iter() {
+            self.clear_avatar(bundle.seller, *key).await?;
+            self.transfer_nft_ownership(*key, buyer).await?;
+        }
+        bundle.status = BundleStatus::Sold;
+        bundle.buyer = Some(buyer);
+        bundle.sold_at = Some(now);
+        self.close_bundle(&bundle).await?;
+        Ok(bundle)
+    }
+
+    pub(crate) async fn nft_metadata_document(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<Option<NFTMetadataDocument>, MarketError> {
+        let Some(collection) = self._collections.get(&collection_id).await? else {
+            return Ok(None);
+        };
+        let Some(nft) = collection.nfts.get(&token_id) else {
+            return Ok(None);
+        };
+        let owner = match nft.is_edition() {
+            true => None,
+            false => Some(self.nft_owner(collection_id, token_id).await?),
+        };
+        Ok(Some(NFTMetadataDocument {
+            name: nft.name.clone(),
+            description: collection.metadata.description.clone().unwrap_or_default(),
+            image: collection.uri(nft.uri_index).unwrap_or_default(),
+            external_url: collection.metadata.external_link.clone(),
+            attributes: nft
+                .attributes
+                .iter()
+                .map(|attribute| MetadataAttribute {
+                    display_type: attribute.numeric.map(|_| "number".to_string()),
+                    trait_type: attribute.key.clone(),
+                    value: match attribute.numeric {
+                        Some(numeric) => numeric.to_string(),
+                        _ => attribute.value.clone(),
+                    },
+                })
+                .chain(std::iter::once(MetadataAttribute {
+                    display_type: Some("date".to_string()),
+                    trait_type: "Minted At".to_string(),
+                    value: (nft.minted_at.micros() / 1_000_000).to_string(),
+                }))
+                .chain(nft.is_edition().then(|| MetadataAttribute {
+                    display_type: Some("number".to_string()),
+                    trait_type: "Editions".to_string(),
+                    value: nft.editions.to_string(),
+                }))
+                .collect(),
+            owner,
+            collection: collection.name.clone(),
+            collection_id,
+            token_id,
+        }))
+    }
+
+    pub(crate) async fn offer(&self, offer_id: u64) -> Result<Offer, MarketError> {
+        match self.offers.get(&offer_id).await? {
+            Some(offer) => Ok(offer),
+            _ => Err(MarketError::OfferNotExists),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn make_offer(
+        &mut self,
+        buyer: Owner,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        credits: Amount,
+        expires_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<u64, MarketError> {
+        if self.nft_owner(collection_id, token_id).await? == buyer {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        let value = amount.saturating_add(self.credits_to_tokens(credits).await?);
+        if expires_at <= now || value == Amount::ZERO {
+            return Err(MarketError::InvalidOffer);
+        }
+        let offer_id = *self.offer_id.get();
+        self.offers.insert(
+            &offer_id,
+            Offer {
+                offer_id,
+                collection_id,
+                token_id,
+                buyer,
+                amount,
+                credits,
+                value,
+                counter: None,
+                countered_by: None,
+                status: OfferStatus::Open,
+                created_at: now,
+                expires_at,
+            },
+        )?;
+        let key = NFTKey {
+            collection_id,
+            token_id,
+        };
+        let mut offer_ids = self.nft_offers.get(&key).await?.unwrap_or_default();
+        offer_ids.push(offer_id);
+        self.nft_offers.insert(&key, offer_ids)?;
+        self.offer_id.set(offer_id + 1);
+        Ok(offer_id)
+    }
+
+    async fn close_offer(
+        &mut self,
+        mut offer: Offer,
+        status: OfferStatus,
+    ) -> Result<Offer, MarketError> {
+        let key = NFTKey {
+            collection_id: offer.collection_id,
+            token_id: offer.token_id,
+        };
+        let mut offer_ids = self.nft_offers.get(&key).await?.unwrap_or_default();
+        offer_ids.retain(|offer_id| *offer_id != offer.offer_id);
+        if offer_ids.is_empty() {
+            self.nft_offers.remove(&key)?;
+        } else {
+            self.nft_offers.insert(&key, offer_ids)?;
+        }
+        offer.status = status;
+        self.offers.insert(&offer.offer_id, offer.clone())?;
+        Ok(offer)
+    }
+
+    /// Expired offers are cancelled by the buyer as well to get the escrow back
+    pub(crate) async fn cancel_offer(
+        &mut self,
+        buyer: Owner,
+        offer_id: u64,
+    ) -> Result<Offer, MarketError> {
+        let offer = self.offer(offer_id).await?;
+        if offer.buyer != buyer {
+            return Err(MarketError::InvalidOwner);
+        }
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        self.close_offer(offer, OfferStatus::Cancelled).await
+    }
+
+    pub(crate) async fn accept_offer(
+        &mut self,
+        owner: Owner,
+        offer_id: u64,
+        now: Timestamp,
+    ) -> Result<Offer, MarketError> {
+        let offer = self.offer(offer_id).await?;
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        if offer.expires_at <= now {
+            return Err(MarketError::OfferExpired);
+        }
+        if !self
+            .escrow_covers_charges(offer.collection_id, owner, offer.value, offer.amount)
+            .await?
+        {
+            return Err(MarketError::InvalidOffer);
+        }
+        self.transfer_nft(owner, offer.collection_id, offer.token_id, offer.buyer)
+            .await?;
+        self.close_offer(offer, OfferStatus::Accepted).await
+    }
+
+    pub(crate) async fn counter_offer(
+        &mut self,
+        owner: Owner,
+        offer_id: u64,
+        price: Amount,
+        now: Timestamp,
+    ) -> Result<Offer, MarketError> {
+        let mut offer = self.offer(offer_id).await?;
+        if self.nft_owner(offer.collection_id, offer.token_id).await? != owner {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        if offer.expires_at <= now {
+            return Err(MarketError::OfferExpired);
+        }
+        if price <= offer.value {
+            return Err(MarketError::InvalidPrice);
+        }
+        offer.counter = Some(price);
+        offer.countered_by = Some(owner);
+        self.offers.insert(&offer_id, offer.clone())?;
+        Ok(offer)
+    }
+
+    /// Buyer takes the counter price, the difference is taken from the deposit of the buyer.
+    /// Returns the accepted offer and the owner sold the token
+    pub(crate) async fn accept_counter(
+        &mut self,
+        buyer: Owner,
+        offer_id: u64,
+        now: Timestamp,
+    ) -> Result<(Offer, Owner), MarketError> {
+        let mut offer = self.offer(offer_id).await?;
+        if offer.buyer != buyer {
+            return Err(MarketError::InvalidOwner);
+        }
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        if offer.expires_at <= now {
+            return Err(MarketError::OfferExpired);
+        }
+        let (price, owner) = match (offer.counter, offer.countered_by) {
+            (Some(price), Some(owner)) => (price, owner),
+            _ => return Err(MarketError::InvalidOffer),
+        };
+        if self.nft_owner(offer.collection_id, offer.token_id).await? != owner {
+            return Err(MarketError::NotTokenOwner);
+        }
+        let difference = price.saturating_sub(offer.value);
+        self.spend_balance(buyer, difference).await?;
+        offer.amount = offer.amount.saturating_add(difference);
+        offer.value = price;
+        if !self
+            .escrow_covers_charges(offer.collection_id, owner, offer.value, offer.amount)
+            .await?
+        {
+            return Err(MarketError::InvalidOffer);
+        }
+        self.transfer_nft(owner, offer.collection_id, offer.token_id, buyer)
+            .await?;
+        let offer = self.close_offer(offer, OfferStatus::Accepted).await?;
+        Ok((offer, owner))
+    }
+
+    /// Publisher and royalty owed when a token is resold, nothing on the first sale
+    pub(crate) async fn royalty(
+        &self,
+        collection_id: u64,
+        seller: Owner,
+        amount: Amount,
+    ) -> Result<Option<(Owner, Amount)>, MarketError> {
+        let collection = match self._collections.get(&collection_id).await? {
+            Some(collection) => collection,
+            _ => return Err(MarketError::CollectionNotExists),
+        };
+        if collection.publisher == seller || collection.royalty_percent == 0 {
+            return Ok(None);
+        }
+        Ok(Some((
+            collection.publisher,
+            Amount::from_attos(
+                Amount::from_attos(collection.royalty_percent as u128)
+                    .saturating_mul(amount.into())
+                    .saturating_div(Amount::from_attos(100 as u128)),
+            ),
+        )))
+    }
+
+    pub(crate) async fn record_royalty(
+        &mut self,
+        publisher: Owner,
+        amount: Amount,
+    ) -> Result<(), MarketError> {
+        let earnings = self
+            .royalty_earnings
+            .get(&publisher)
+            .await?
+            .unwrap_or_default();
+        self.royalty_earnings
+            .insert(&publisher, earnings.saturating_add(amount))?;
+        Ok(())
+    }
+
+    pub(crate) async fn record_sale(&mut self, mut sale: Sale) -> Result<(), MarketError> {
+        let sale_id = *self.sale_id.get();
+        sale.sale_id = sale_id;
+        let key = NFTKey {
+            collection_id: sale.collection_id,
+            token_id: sale.token_id,
+        };
+        let mut sale_ids = self.token_sales.get(&key).await?.unwrap_or_default();
+        sale_ids.push(sale_id);
+        self.token_sales.insert(&key, sale_ids)?;
+        let mut sale_ids = self
+            .collection_sales
+            .get(&sale.collection_id)
+            .await?
+            .unwrap_or_default();
+        sale_ids.push(sale_id);
+        self.collection_sales
+            .insert(&sale.collection_id, sale_ids)?;
+        self.sales.insert(&sale_id, sale)?;
+        self.sale_id.set(sale_id + 1);
+        Ok(())
+    }
+
+    pub(crate) async fn trading_fee(
+        &self,
+        collection_id: u64,
+        amount: Amount,
+    ) -> Result<SaleFees, MarketError> {
+        Ok(self
+            .fee_schedule
+            .get()
+            .rate(collection_id, amount)
+            .fees(amount))
+    }
+
+    pub(crate) async fn update_fee_schedule(
+        &mut self,
+        schedule: FeeSchedule,
+    ) -> Result<(), MarketError> {
+        if !schedule.is_valid(
+            *self.max_royalty_percent.get(),
+            *self.max_credits_percent.get(),
+        ) {
+            return Err(MarketError::InvalidFeeSchedule);
+        }
+        self.fee_schedule.set(schedule);
+        Ok(())
+    }
+
+    /// Fees and royalty are charged on the full value but only paid in escrowed tokens
+    pub(crate) async fn escrow_covers_charges(
+        &self,
+        collection_id: u64,
+        seller: Owner,
+        value: Amount,
+        amount: Amount,
+    ) -> Result<bool, MarketError> {
+        let fees = self.trading_fee(collection_id, value).await?;
+        let royalty = self
+            .royalty(collection_id, seller, value)
+            .await?
+            .map_or(Amount::ZERO, |(_, royalty)| royalty);
+        Ok(fees.total().saturating_add(royalty) <= amount)
+    }
+
+    /// Seller proceeds and buyer payment once fees, royalty and credits discount are applied
+    pub(crate) async fn quote_sale(
+        &self,
+        price: Amount,
+        credits: Amount,
+        fees: SaleFees,
+        royalty: Amount,
+    ) -> Result<Quote, MarketError> {
+        let discount = self.credits_to_tokens(credits).await?;
+        let proceeds = price
+            .saturating_sub(fees.total())
+            .saturating_sub(royalty)
+            .saturating_sub(discount);
+        Ok(Quote {
+            price,
+            credits,
+            discount,
+            fees,
+            royalty,
+            proceeds,
+            payment: proceeds
+                .saturating_add(fees.total())
+                .saturating_add(royalty),
+        })
+    }
+
+    /// Outstanding credits per Linera held by foundation, at nano token precision so
+    /// that supplies of realistic size do not overflow
+    pub(crate) fn supply_rate(outstanding: Amount, foundation: Amount) -> Option<Amount> {
+        const NANO: u128 = 1_000_000_000;
+        let foundation = u128::from(foundation) / NANO;
+        if foundation == 0 {
+            return None;
+        }
+        Some(Amount::from_attos(
+            (u128::from(outstanding) / NANO).saturating_mul(u128::from(Amount::ONE)) / foundation,
+        ))
+    }
+
+    async fn record_rate(&mut self, rate: CreditsPerLineraRate) -> Result<(), MarketError> {
+        let rate_id = *self.rate_id.get();
+        self.credits_per_linera.set(rate.credits_per_linera);
+        self.rates.insert(&rate_id, rate)?;
+        self.rate_id.set(rate_id + 1);
+        Ok(())
+    }
+
+    /// Move the rate part of the way to target, never further than the per epoch bound
+    pub(crate) async fn refresh_credits_per_linera(
+        &mut self,
+        target: Amount,
+        now: Timestamp,
+    ) -> Result<Amount, MarketError> {
+        if let Some(updated_at) = *self.oracle_updated_at.get() {
+            let epoch_micros = self.rate_epoch_ms.get().saturating_mul(1000);
+            if now < updated_at.saturating_add_micros(epoch_micros) {
+                return Err(MarketError::RateEpochNotEnded);
+            }
+        }
+        let current = *self.credits_per_linera.get();
+        let percent = |amount: Amount, percent: u8| {
+            Amount::from_attos(u128::from(amount) / 100 * percent as u128)
+        };
+        let step = percent(
+            target.max(current).saturating_sub(target.min(current)),
+            *self.rate_smoothing_percent.get(),
+        )
+        .min(percent(current, *self.max_rate_change_percent.get()));
+        let credits_per_linera = match target > current {
+            true => current.saturating_add(step),
+            false => current.saturating_sub(step),
+        };
+        if credits_per_linera == Amount::ZERO {
+            return Err(MarketError::InvalidRateSource);
+        }
+        self.oracle_updated_at.set(Some(now));
+        self.record_rate(CreditsPerLineraRate {
+            credits_per_linera,
+            target: Some(target),
+            source: RateSource::Oracle,
+            updated_at: now,
+        })
+        .await?;
+        Ok(credits_per_linera)
+    }
+
+    /// Admin override, not bounded and not counted as the epoch's oracle update
+    pub(crate) async fn override_credits_per_linera(
+        &mut self,
+        credits_per_linera: Amount,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        if credits_per_linera == Amount::ZERO {
+            return Err(MarketError::InvalidAmount);
+        }
+        self.record_rate(CreditsPerLineraRate {
+            credits_per_linera,
+            target: None,
+            source: RateSource::Admin,
+            updated_at: now,
+        })
+        .await
     }
 
     pub(crate) async fn credits_to_tokens(&self, credits: Amount) -> Result<Amount, MarketError> {
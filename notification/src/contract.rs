@@ -0,0 +1,274 @@
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use self::state::Notification;
+use linera_sdk::{
+    base::{ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    views::{RootView, View},
+    Contract, ContractRuntime,
+};
+use notification::{Message, NotificationAbi, NotificationError, NotificationKind, Operation};
+
+const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
+
+pub struct NotificationContract {
+    state: Notification,
+    runtime: ContractRuntime<Self>,
+}
+
+linera_sdk::contract!(NotificationContract);
+
+impl WithContractAbi for NotificationContract {
+    type Abi = NotificationAbi;
+}
+
+impl Contract for NotificationContract {
+    type Message = Message;
+    type InstantiationArgument = ();
+    type Parameters = ();
+
+    async fn load(runtime: ContractRuntime<Self>) -> Self {
+        let state = Notification::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        NotificationContract { state, runtime }
+    }
+
+    async fn instantiate(&mut self, _argument: ()) {
+        self.runtime.application_parameters();
+    }
+
+    async fn execute_operation(&mut self, operation: Operation) -> Self::Response {
+        match operation {
+            Operation::Notify {
+                owner,
+                kind,
+                subject,
+                message,
+            } => self
+                .on_op_notify(owner, kind, subject, message)
+                .await
+                .expect("Failed OP: notify"),
+            Operation::MarkRead { ids } => self.on_op_mark_read(ids).expect("Failed OP: mark read"),
+            Operation::MarkAllRead => self
+                .on_op_mark_all_read()
+                .expect("Failed OP: mark all read"),
+            Operation::RequestSubscribe => self
+                .on_op_request_subscribe()
+                .expect("Failed OP: subscribe"),
+            Operation::SetNotifyCallers { application_ids } => self
+                .on_op_set_notify_callers(application_ids)
+                .expect("Failed OP: set notify callers"),
+        }
+    }
+
+    async fn execute_message(&mut self, message: Message) {
+        match message {
+            Message::Notify {
+                owner,
+                kind,
+                subject,
+                message,
+            } => self
+                .on_msg_notify(owner, kind, subject, message)
+                .await
+                .expect("Failed MSG: notify"),
+            Message::MarkRead { ids } => self
+                .on_msg_mark_read(ids)
+                .await
+                .expect("Failed MSG: mark read"),
+            Message::MarkAllRead => self
+                .on_msg_mark_all_read()
+                .await
+                .expect("Failed MSG: mark all read"),
+            Message::RequestSubscribe => self
+                .on_msg_request_subscribe()
+                .expect("Failed MSG: subscribe"),
+            Message::SetNotifyCallers { application_ids } => self
+                .on_msg_set_notify_callers(application_ids)
+                .await
+                .expect("Failed MSG: set notify callers"),
+        }
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl NotificationContract {
+    fn require_message_id(&mut self) -> Result<MessageId, NotificationError> {
+        match self.runtime.message_id() {
+            Some(message_id) => Ok(message_id),
+            None => Err(NotificationError::InvalidMessageId),
+        }
+    }
+
+    fn require_authenticated_signer(&mut self) -> Result<Owner, NotificationError> {
+        match self.runtime.authenticated_signer() {
+            Some(owner) => Ok(owner),
+            None => Err(NotificationError::InvalidSigner),
+        }
+    }
+
+    async fn on_op_notify(
+        &mut self,
+        owner: Owner,
+        kind: NotificationKind,
+        subject: String,
+        message: String,
+    ) -> Result<(), NotificationError> {
+        // Users could not notify others directly, only whitelisted applications could
+        let caller_id = match self.runtime.authenticated_caller_id() {
+            Some(caller_id) => caller_id,
+            None => return Err(NotificationError::CallerNotAllowed),
+        };
+        if !self.state.is_notify_caller(caller_id).await? {
+            return Err(NotificationError::CallerNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::Notify {
+                owner,
+                kind,
+                subject,
+                message,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_mark_read(&mut self, ids: Vec<u64>) -> Result<(), NotificationError> {
+        self.runtime
+            .prepare_message(Message::MarkRead { ids })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_mark_all_read(&mut self) -> Result<(), NotificationError> {
+        self.runtime
+            .prepare_message(Message::MarkAllRead)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_request_subscribe(&mut self) -> Result<(), NotificationError> {
+        self.runtime
+            .prepare_message(Message::RequestSubscribe)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_set_notify_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), NotificationError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(NotificationError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetNotifyCallers { application_ids })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    // Inbox changes are only sent to the chain of the owner instead of all subscribers
+    async fn send_to_owner_chain(
+        &mut self,
+        owner: Owner,
+        message: Message,
+    ) -> Result<(), NotificationError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let chain_id = match self.state.owner_chain(owner).await? {
+            Some(chain_id) => chain_id,
+            None => return Ok(()),
+        };
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .send_to(chain_id);
+        Ok(())
+    }
+
+    async fn on_msg_notify(
+        &mut self,
+        owner: Owner,
+        kind: NotificationKind,
+        subject: String,
+        message: String,
+    ) -> Result<(), NotificationError> {
+        self.state
+            .notify(
+                owner,
+                kind,
+                subject.clone(),
+                message.clone(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        self.send_to_owner_chain(
+            owner,
+            Message::Notify {
+                owner,
+                kind,
+                subject,
+                message,
+            },
+        )
+        .await
+    }
+
+    async fn on_msg_mark_read(&mut self, ids: Vec<u64>) -> Result<(), NotificationError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state.mark_read(owner, ids.clone()).await?;
+        self.send_to_owner_chain(owner, Message::MarkRead { ids })
+            .await
+    }
+
+    async fn on_msg_mark_all_read(&mut self) -> Result<(), NotificationError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state.mark_all_read(owner).await?;
+        self.send_to_owner_chain(owner, Message::MarkAllRead).await
+    }
+
+    fn on_msg_request_subscribe(&mut self) -> Result<(), NotificationError> {
+        let message_id = self.require_message_id()?;
+        // The subscribe message must be from another chain
+        if message_id.chain_id == self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.runtime.subscribe(
+            message_id.chain_id,
+            ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()),
+        );
+        let owner = self.require_authenticated_signer()?;
+        self.state.set_owner_chain(owner, message_id.chain_id)?;
+        Ok(())
+    }
+
+    async fn on_msg_set_notify_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), NotificationError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(NotificationError::OperationNotAllowed);
+        }
+        self.state.set_notify_callers(application_ids.clone()).await;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetNotifyCallers { application_ids })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+}
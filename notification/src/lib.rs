@@ -0,0 +1,140 @@
+use async_graphql::{Enum, Request, Response, SimpleObject};
+use linera_sdk::{
+    base::{ApplicationId, ContractAbi, Owner, ServiceAbi, Timestamp},
+    graphql::GraphQLMutationRoot,
+    Contract, ContractRuntime,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub struct NotificationAbi;
+
+impl ContractAbi for NotificationAbi {
+    type Operation = Operation;
+    type Response = ();
+}
+
+impl ServiceAbi for NotificationAbi {
+    type Query = Request;
+    type QueryResponse = Response;
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum NotificationKind {
+    ContentApproved,
+    ContentRejected,
+    ContentPublished,
+    CommentReceived,
+    AssetApproved,
+    AssetRejected,
+    SubmissionExpired,
+    AppealResolved,
+    ReviewerApproved,
+    ReviewerRejected,
+    ReviewerSuspended,
+    ActivityApproved,
+    ActivityRejected,
+    ActivityWon,
+    NFTSold,
+    RewardReceived,
+    AuctionOutbid,
+    OfferReceived,
+    OfferAccepted,
+    OfferCountered,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Notification {
+    /// Sequence of the notification in inbox of the owner
+    pub id: u64,
+    pub kind: NotificationKind,
+    /// Id of the object the notification is about, e.g. cid of content
+    pub subject: String,
+    pub message: String,
+    pub read: bool,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
+pub enum Operation {
+    /// Only called by other applications
+    Notify {
+        owner: Owner,
+        kind: NotificationKind,
+        subject: String,
+        message: String,
+    },
+    MarkRead {
+        ids: Vec<u64>,
+    },
+    MarkAllRead,
+    RequestSubscribe,
+    SetNotifyCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    Notify {
+        owner: Owner,
+        kind: NotificationKind,
+        subject: String,
+        message: String,
+    },
+    MarkRead {
+        ids: Vec<u64>,
+    },
+    MarkAllRead,
+    RequestSubscribe,
+    SetNotifyCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+}
+
+/// Sends a notification to owner inbox, the notification application only accepts whitelisted
+/// callers
+pub fn notify<Application: Contract>(
+    runtime: &mut ContractRuntime<Application>,
+    application_id: ApplicationId<NotificationAbi>,
+    owner: Owner,
+    kind: NotificationKind,
+    subject: String,
+    message: String,
+) {
+    let call = Operation::Notify {
+        owner,
+        kind,
+        subject,
+        message,
+    };
+    runtime.call_application(true, application_id, &call);
+}
+
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    #[error("View error")]
+    ViewError(#[from] linera_views::views::ViewError),
+
+    #[error("Failed to deserialize BCS bytes")]
+    BcsError(#[from] bcs::Error),
+
+    /// Failed to deserialize JSON string
+    #[error("Failed to deserialize JSON string")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Caller not allowed")]
+    CallerNotAllowed,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
+
+    #[error("Invalid signer")]
+    InvalidSigner,
+
+    #[error("Invalid message id")]
+    InvalidMessageId,
+
+    #[error("Invalid notification")]
+    InvalidNotification,
+}
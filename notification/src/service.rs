@@ -0,0 +1,44 @@
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+mod state;
+
+use self::state::Notification;
+use async_graphql::{EmptySubscription, Request, Response, Schema};
+use linera_sdk::{
+    base::WithServiceAbi, graphql::GraphQLMutationRoot, views::View, Service, ServiceRuntime,
+};
+use notification::Operation;
+use std::sync::Arc;
+
+pub struct NotificationService {
+    state: Arc<Notification>,
+}
+
+linera_sdk::service!(NotificationService);
+
+impl WithServiceAbi for NotificationService {
+    type Abi = notification::NotificationAbi;
+}
+
+impl Service for NotificationService {
+    type Parameters = ();
+
+    async fn new(runtime: ServiceRuntime<Self>) -> Self {
+        let state = Notification::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
+        NotificationService {
+            state: Arc::new(state),
+        }
+    }
+
+    async fn handle_query(&self, request: Request) -> Response {
+        let schema = Schema::build(
+            self.state.clone(),
+            Operation::mutation_root(),
+            EmptySubscription,
+        )
+        .finish();
+        schema.execute(request).await
+    }
+}
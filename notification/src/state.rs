@@ -0,0 +1,128 @@
+use async_graphql::{ComplexObject, SimpleObject};
+use linera_sdk::{
+    base::{ApplicationId, ChainId, Owner, Timestamp},
+    views::{linera_views, MapView, RootView, SetView, ViewStorageContext},
+};
+use notification::{Notification as _Notification, NotificationError, NotificationKind};
+
+#[derive(RootView, SimpleObject)]
+#[view(context = "ViewStorageContext")]
+#[graphql(complex)]
+pub struct Notification {
+    pub notifications: MapView<Owner, Vec<_Notification>>,
+    pub unread_counts: MapView<Owner, u64>,
+    /// Chain of the owner inbox, notifications are only sent there
+    pub owner_chains: MapView<Owner, ChainId>,
+    /// Applications allowed to notify users
+    pub notify_callers: SetView<ApplicationId>,
+}
+
+#[ComplexObject]
+impl Notification {
+    /// Inbox of the owner, the latest notification first
+    async fn inbox(
+        &self,
+        owner: Owner,
+        unread_only: Option<bool>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<_Notification>> {
+        let notifications = self.notifications.get(&owner).await?.unwrap_or_default();
+        let unread_only = unread_only.unwrap_or_default();
+        Ok(notifications
+            .into_iter()
+            .rev()
+            .filter(|notification| !unread_only || !notification.read)
+            .skip(offset.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+}
+
+#[allow(dead_code)]
+impl Notification {
+    pub(crate) async fn notify(
+        &mut self,
+        owner: Owner,
+        kind: NotificationKind,
+        subject: String,
+        message: String,
+        now: Timestamp,
+    ) -> Result<(), NotificationError> {
+        let mut notifications = self.notifications.get(&owner).await?.unwrap_or_default();
+        notifications.push(_Notification {
+            id: notifications.len() as u64,
+            kind,
+            subject,
+            message,
+            read: false,
+            created_at: now,
+        });
+        self.notifications.insert(&owner, notifications)?;
+        let unread = self.unread_counts.get(&owner).await?.unwrap_or_default();
+        self.unread_counts.insert(&owner, unread + 1)?;
+        Ok(())
+    }
+
+    pub(crate) async fn mark_read(
+        &mut self,
+        owner: Owner,
+        ids: Vec<u64>,
+    ) -> Result<(), NotificationError> {
+        let mut notifications = self.notifications.get(&owner).await?.unwrap_or_default();
+        let mut unread = self.unread_counts.get(&owner).await?.unwrap_or_default();
+        for id in ids {
+            match notifications.get_mut(id as usize) {
+                Some(notification) => {
+                    if !notification.read {
+                        notification.read = true;
+                        unread = unread.saturating_sub(1);
+                    }
+                }
+                None => return Err(NotificationError::InvalidNotification),
+            }
+        }
+        self.notifications.insert(&owner, notifications)?;
+        self.unread_counts.insert(&owner, unread)?;
+        Ok(())
+    }
+
+    pub(crate) async fn owner_chain(
+        &self,
+        owner: Owner,
+    ) -> Result<Option<ChainId>, NotificationError> {
+        Ok(self.owner_chains.get(&owner).await?)
+    }
+
+    pub(crate) fn set_owner_chain(
+        &mut self,
+        owner: Owner,
+        chain_id: ChainId,
+    ) -> Result<(), NotificationError> {
+        self.owner_chains.insert(&owner, chain_id)?;
+        Ok(())
+    }
+
+    pub(crate) async fn set_notify_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        application_ids
+            .iter()
+            .for_each(|application_id| self.notify_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn is_notify_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, NotificationError> {
+        Ok(self.notify_callers.contains(&application_id).await?)
+    }
+
+    pub(crate) async fn mark_all_read(&mut self, owner: Owner) -> Result<(), NotificationError> {
+        let mut notifications = self.notifications.get(&owner).await?.unwrap_or_default();
+        notifications
+            .iter_mut()
+            .for_each(|notification| notification.read = true);
+        self.notifications.insert(&owner, notifications)?;
+        self.unread_counts.insert(&owner, 0)?;
+        Ok(())
+    }
+}
@@ -2,24 +2,30 @@
 
 mod state;
 
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
 
 use self::state::Review;
-use credit::CreditAbi;
-use feed::FeedAbi;
+use credit::{ApplicationEscrow, CreditAbi, CreditResponse};
+use feed::{FeedAbi, FeedResponse, ReportReason, ReportedContent};
 use foundation::FoundationAbi;
 use linera_sdk::{
     base::{
-        Amount, ApplicationId, ChainId, ChannelName, Destination, MessageId, Owner, WithContractAbi,
+        Amount, ApplicationId, ChainId, ChannelName, Destination, MessageId, Owner, Timestamp,
+        WithContractAbi,
     },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 // use linera_views::views::ViewError;
-use market::MarketAbi;
+use market::{CollectionMetadata, MarketAbi};
+use notification::{NotificationAbi, NotificationKind};
 use review::{
-    Asset, Content, InstantiationArgument, Message, Operation, ReviewError, ReviewParameters,
-    ReviewResponse, Reviewer,
+    Asset, Content, ContentReport, ExamAnswer, ExamAnswerInput, InstantiationArgument, Message,
+    Operation, PendingSubmission, QuorumRule, QuorumRuleInput, ReviewError, ReviewParameters,
+    ReviewResponse, Reviewer, RubricScore, RubricScoreInput, SubmissionKind, SubmissionStatus,
 };
 
 pub struct ReviewContract {
@@ -34,6 +40,11 @@ impl WithContractAbi for ReviewContract {
 }
 
 const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
+const APPEAL_KINDS: &[SubmissionKind] = &[
+    SubmissionKind::Content,
+    SubmissionKind::Comment,
+    SubmissionKind::Asset,
+];
 
 impl Contract for ReviewContract {
     type Message = Message;
@@ -86,14 +97,16 @@ impl Contract for ReviewContract {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             } => self
-                .on_op_approve_content(content_cid, reason_cid, reason)
+                .on_op_approve_content(content_cid, reason_cid, reason, scores)
                 .expect("Failed OP: approve content"),
             Operation::RejectContent {
                 content_cid,
                 reason,
+                scores,
             } => self
-                .on_op_reject_content(content_cid, reason)
+                .on_op_reject_content(content_cid, reason, scores)
                 .expect("Failed OP: reject content"),
             Operation::SubmitComment {
                 cid,
@@ -102,11 +115,19 @@ impl Contract for ReviewContract {
             } => self
                 .on_op_submit_comment(cid, comment_cid, comment)
                 .expect("Failed OP: submit comment"),
-            Operation::ApproveAsset { cid, reason } => self
-                .on_op_approve_asset(cid, reason)
+            Operation::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_op_approve_asset(cid, reason, scores)
                 .expect("Failed OP: approve asset"),
-            Operation::RejectAsset { cid, reason } => self
-                .on_op_reject_asset(cid, reason)
+            Operation::RejectAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_op_reject_asset(cid, reason, scores)
                 .expect("Failed OP: reject asset"),
             Operation::SubmitAsset {
                 cid,
@@ -114,8 +135,10 @@ impl Contract for ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
+                metadata,
             } => self
-                .on_op_submit_asset(cid, base_uri, uris, price, name)
+                .on_op_submit_asset(cid, base_uri, uris, price, name, royalty_percent, metadata)
                 .expect("Failed OP: submit asset"),
             Operation::RequestSubscribe => self
                 .on_op_request_subscribe()
@@ -143,6 +166,46 @@ impl Contract for ReviewContract {
                 .on_op_activity_approved(activity_id)
                 .await
                 .expect("Failed OP: activity approved"),
+            Operation::Report {
+                cid,
+                reason_category,
+                note,
+            } => self
+                .on_op_report(cid, reason_category, note)
+                .expect("Failed OP: report"),
+            Operation::UpholdReport { cid, reason } => self
+                .on_op_uphold_report(cid, reason)
+                .expect("Failed OP: uphold report"),
+            Operation::DismissReport { cid, reason } => self
+                .on_op_dismiss_report(cid, reason)
+                .expect("Failed OP: dismiss report"),
+            Operation::RemoveReviewer { candidate, reason } => self
+                .on_op_remove_reviewer(candidate, reason)
+                .expect("Failed OP: remove reviewer"),
+            Operation::ResumeReviewer { candidate, reason } => self
+                .on_op_resume_reviewer(candidate, reason)
+                .expect("Failed OP: resume reviewer"),
+            Operation::SuspendInactiveReviewers { reviewers } => self
+                .on_op_suspend_inactive_reviewers(reviewers)
+                .expect("Failed OP: suspend inactive reviewers"),
+            Operation::UpdateQuorumRule { kind, rule } => self
+                .on_op_update_quorum_rule(kind, rule)
+                .expect("Failed OP: update quorum rule"),
+            Operation::Appeal { cid, statement } => self
+                .on_op_appeal(cid, statement)
+                .expect("Failed OP: appeal"),
+            Operation::ApproveAppeal { cid, reason } => self
+                .on_op_approve_appeal(cid, reason)
+                .expect("Failed OP: approve appeal"),
+            Operation::RejectAppeal { cid, reason } => self
+                .on_op_reject_appeal(cid, reason)
+                .expect("Failed OP: reject appeal"),
+            Operation::TakeReviewerExam { answers } => self
+                .on_op_take_reviewer_exam(answers)
+                .expect("Failed OP: take reviewer exam"),
+            Operation::ExpireSubmission { kind, id } => self
+                .on_op_expire_submission(kind, id)
+                .expect("Failed OP: expire submission"),
         }
     }
 
@@ -186,15 +249,17 @@ impl Contract for ReviewContract {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             } => self
-                .on_msg_approve_content(content_cid, reason_cid, reason)
+                .on_msg_approve_content(content_cid, reason_cid, reason, scores)
                 .await
                 .expect("Failed MSG: approve content"),
             Message::RejectContent {
                 content_cid,
                 reason,
+                scores,
             } => self
-                .on_msg_reject_content(content_cid, reason)
+                .on_msg_reject_content(content_cid, reason, scores)
                 .await
                 .expect("Failed MSG: reject content"),
             Message::SubmitComment {
@@ -205,12 +270,20 @@ impl Contract for ReviewContract {
                 .on_msg_submit_comment(cid, comment_cid, comment)
                 .await
                 .expect("Failed MSG: submit comment"),
-            Message::ApproveAsset { cid, reason } => self
-                .on_msg_approve_asset(cid, reason)
+            Message::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_msg_approve_asset(cid, reason, scores)
                 .await
                 .expect("Failed MSG: approve asset"),
-            Message::RejectAsset { cid, reason } => self
-                .on_msg_reject_asset(cid, reason)
+            Message::RejectAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_msg_reject_asset(cid, reason, scores)
                 .await
                 .expect("Failed MSG: reject asset"),
             Message::SubmitAsset {
@@ -219,8 +292,10 @@ impl Contract for ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
+                metadata,
             } => self
-                .on_msg_submit_asset(cid, base_uri, uris, price, name)
+                .on_msg_submit_asset(cid, base_uri, uris, price, name, royalty_percent, metadata)
                 .await
                 .expect("Failed MSG: submit asset"),
             Message::RequestSubscribe => self
@@ -253,6 +328,78 @@ impl Contract for ReviewContract {
                 .on_msg_reject_activity(activity_id, reason)
                 .await
                 .expect("Failed MSG: reject activity"),
+            Message::SubmitReport { report } => self
+                .on_msg_submit_report(report)
+                .await
+                .expect("Failed MSG: submit report"),
+            Message::Report {
+                cid,
+                reason_category,
+                note,
+            } => self
+                .on_msg_report(cid, reason_category, note)
+                .await
+                .expect("Failed MSG: report"),
+            Message::UpholdReport { cid, reason } => self
+                .on_msg_uphold_report(cid, reason)
+                .await
+                .expect("Failed MSG: uphold report"),
+            Message::DismissReport { cid, reason } => self
+                .on_msg_dismiss_report(cid, reason)
+                .await
+                .expect("Failed MSG: dismiss report"),
+            Message::RemoveReviewer { candidate, reason } => self
+                .on_msg_remove_reviewer(candidate, reason)
+                .await
+                .expect("Failed MSG: remove reviewer"),
+            Message::ResumeReviewer { candidate, reason } => self
+                .on_msg_resume_reviewer(candidate, reason)
+                .await
+                .expect("Failed MSG: resume reviewer"),
+            Message::SuspendInactiveReviewers { reviewers } => self
+                .on_msg_suspend_inactive_reviewers(reviewers)
+                .await
+                .expect("Failed MSG: suspend inactive reviewers"),
+            Message::SuspendReviewers { reviewers } => self
+                .on_msg_suspend_reviewers(reviewers)
+                .await
+                .expect("Failed MSG: suspend reviewers"),
+            Message::UpdateQuorumRule { kind, rule } => self
+                .on_msg_update_quorum_rule(kind, rule)
+                .await
+                .expect("Failed MSG: update quorum rule"),
+            Message::AssignReviewers {
+                kind,
+                id,
+                assignments,
+            } => self
+                .on_msg_assign_reviewers(kind, id, assignments)
+                .await
+                .expect("Failed MSG: assign reviewers"),
+            Message::ExpireSubmission { kind, id } => self
+                .on_msg_expire_submission(kind, id)
+                .await
+                .expect("Failed MSG: expire submission"),
+            Message::ExpireSubmissions { submissions } => self
+                .on_msg_expire_submissions(submissions)
+                .await
+                .expect("Failed MSG: expire submissions"),
+            Message::Appeal { cid, statement } => self
+                .on_msg_appeal(cid, statement)
+                .await
+                .expect("Failed MSG: appeal"),
+            Message::ApproveAppeal { cid, reason } => self
+                .on_msg_approve_appeal(cid, reason)
+                .await
+                .expect("Failed MSG: approve appeal"),
+            Message::RejectAppeal { cid, reason } => self
+                .on_msg_reject_appeal(cid, reason)
+                .await
+                .expect("Failed MSG: reject appeal"),
+            Message::TakeReviewerExam { answers } => self
+                .on_msg_take_reviewer_exam(answers)
+                .await
+                .expect("Failed MSG: take reviewer exam"),
         }
     }
 
@@ -278,13 +425,54 @@ impl ReviewContract {
         self.runtime.application_parameters().market_app_id
     }
 
+    fn notification_app_id(&mut self) -> ApplicationId<NotificationAbi> {
+        self.runtime.application_parameters().notification_app_id
+    }
+
     async fn reward_credits(&mut self, owner: Owner, amount: Amount) -> Result<(), ReviewError> {
         let call = credit::Operation::Reward { owner, amount };
         let credit_app_id = self.credit_app_id();
-        self.runtime.call_application(true, credit_app_id, &call);
+        let _ = self.runtime.call_application(true, credit_app_id, &call);
+        Ok(())
+    }
+
+    async fn penalize_credits(&mut self, owner: Owner, amount: Amount) -> Result<(), ReviewError> {
+        let call = credit::Operation::Penalize { owner, amount };
+        let credit_app_id = self.credit_app_id();
+        let _ = self.runtime.call_application(true, credit_app_id, &call);
         Ok(())
     }
 
+    async fn transfer_credits(
+        &mut self,
+        from: Owner,
+        to: Owner,
+        amount: Amount,
+    ) -> Result<(), ReviewError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        let call = credit::Operation::Transfer { from, to, amount };
+        let credit_app_id = self.credit_app_id();
+        match self.runtime.call_application(true, credit_app_id, &call) {
+            CreditResponse::Ok => Ok(()),
+            _ => Err(ReviewError::InsufficientCredits),
+        }
+    }
+
+    fn spendable_credits(&mut self, owner: Owner) -> Result<Amount, ReviewError> {
+        let call = credit::Operation::Spendable { owner };
+        let credit_app_id = self.credit_app_id();
+        match self.runtime.call_application(true, credit_app_id, &call) {
+            CreditResponse::Spendable(amount) => Ok(amount),
+            _ => Err(ReviewError::InsufficientCredits),
+        }
+    }
+
+    fn escrow_owner(&mut self) -> Owner {
+        ApplicationEscrow::owner(self.runtime.application_id().forget_abi())
+    }
+
     async fn reward_tokens(&mut self) -> Result<(), ReviewError> {
         let call = foundation::Operation::Reward {
             reward_user: None,
@@ -305,6 +493,7 @@ impl ReviewContract {
         author: Owner,
         cover: String,
         abbreviation: String,
+        score: u16,
     ) -> Result<(), ReviewError> {
         let call = feed::Operation::Publish {
             cid: cid.clone(),
@@ -313,6 +502,7 @@ impl ReviewContract {
             author,
             cover,
             abbreviation,
+            score,
         };
         let feed_app_id = self.feed_app_id();
         self.runtime.call_application(true, feed_app_id, &call);
@@ -341,18 +531,21 @@ impl ReviewContract {
         comment_cid: String,
         comment: String,
         commentor: Owner,
+        score: u16,
     ) -> Result<(), ReviewError> {
         let call = feed::Operation::Comment {
             cid: cid.clone(),
             comment_cid: comment_cid.clone(),
             comment,
             commentor,
+            score,
         };
         let feed_app_id = self.feed_app_id();
         self.runtime.call_application(true, feed_app_id, &call);
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn create_collection(
         &mut self,
         base_uri: String,
@@ -360,6 +553,8 @@ impl ReviewContract {
         price: Option<Amount>,
         name: String,
         publisher: Owner,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
     ) -> Result<(), ReviewError> {
         let call = market::Operation::CreateCollection {
             base_uri: base_uri.clone(),
@@ -367,6 +562,8 @@ impl ReviewContract {
             name,
             uris,
             publisher,
+            royalty_percent,
+            metadata,
         };
         let market_app_id = self.market_app_id();
         self.runtime.call_application(true, market_app_id, &call);
@@ -388,6 +585,185 @@ impl ReviewContract {
         Ok(())
     }
 
+    fn content_report(&mut self, reported: ReportedContent) -> ContentReport {
+        ContentReport {
+            cid: reported.cid,
+            author: reported.author,
+            reporters: reported
+                .reports
+                .iter()
+                .map(|report| report.reporter)
+                .collect(),
+            reviewers: HashMap::default(),
+            upheld: 0,
+            dismissed: 0,
+            created_at: self.runtime.system_time(),
+        }
+    }
+
+    async fn reported_content(&mut self, cid: String) -> Result<ContentReport, ReviewError> {
+        let call = feed::Operation::ReportedContent { cid };
+        let feed_app_id = self.feed_app_id();
+        match self.runtime.call_application(true, feed_app_id, &call) {
+            FeedResponse::ReportedContent(Some(reported)) => Ok(self.content_report(reported)),
+            _ => Err(ReviewError::InvalidReport),
+        }
+    }
+
+    // Feed records the report of the signer and returns the content once it enters the abuse queue
+    async fn file_report(
+        &mut self,
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    ) -> Result<Option<ContentReport>, ReviewError> {
+        let call = feed::Operation::Report {
+            cid,
+            reason_category,
+            note,
+        };
+        let feed_app_id = self.feed_app_id();
+        match self.runtime.call_application(true, feed_app_id, &call) {
+            FeedResponse::ReportedContent(Some(reported)) => {
+                Ok(Some(self.content_report(reported)))
+            }
+            FeedResponse::ReportedContent(None) => Ok(None),
+            _ => Err(ReviewError::InvalidReport),
+        }
+    }
+
+    async fn resolve_report(&mut self, cid: String, upheld: bool) -> Result<(), ReviewError> {
+        let call = feed::Operation::ResolveReport { cid, upheld };
+        let feed_app_id = self.feed_app_id();
+        self.runtime.call_application(true, feed_app_id, &call);
+        Ok(())
+    }
+
+    async fn interacted(
+        &mut self,
+        owner: Owner,
+        author: Owner,
+        since: Timestamp,
+    ) -> Result<bool, ReviewError> {
+        let call = feed::Operation::Interacted {
+            owner,
+            author,
+            since,
+        };
+        let feed_app_id = self.feed_app_id();
+        match self.runtime.call_application(true, feed_app_id, &call) {
+            FeedResponse::Interacted(interacted) => Ok(interacted),
+            _ => Ok(false),
+        }
+    }
+
+    async fn validate_conflict_of_interest(
+        &mut self,
+        reviewer: Owner,
+        author: Owner,
+    ) -> Result<(), ReviewError> {
+        if author == reviewer {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let conflict_of_interest_days = *self.state.conflict_of_interest_days.get() as u64;
+        if conflict_of_interest_days == 0 {
+            return Ok(());
+        }
+        let since = Timestamp::from(
+            self.runtime
+                .system_time()
+                .micros()
+                .saturating_sub(conflict_of_interest_days * 24 * 3600 * 1_000_000),
+        );
+        if self.interacted(reviewer, author, since).await? {
+            return Err(ReviewError::ConflictOfInterest);
+        }
+        Ok(())
+    }
+
+    // Conflict of interest and assignment are only judged on creation chain
+    async fn validate_review_eligibility(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        reviewer: Owner,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let author = self.state.submission_author(kind, id.clone()).await?;
+        self.validate_conflict_of_interest(reviewer, author).await?;
+        let now = self.runtime.system_time();
+        if let Some(assignments) = self
+            .state
+            .claim_assignment(kind, id.clone(), reviewer, now)
+            .await?
+        {
+            let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+            self.runtime
+                .prepare_message(Message::AssignReviewers {
+                    kind,
+                    id,
+                    assignments,
+                })
+                .with_authentication()
+                .send_to(dest);
+        }
+        Ok(())
+    }
+
+    // Deterministic rotation rather than random assignment: the seed is derived from past reviews
+    // and the block height of the creation chain. The author can't pick it through the submitted
+    // content, but anyone reading the state could predict it and time a submission for it
+    async fn assign_reviewers(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        author: Owner,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let assigned_reviewers = *self.state.assigned_reviewers.get() as usize;
+        if assigned_reviewers == 0 {
+            return Ok(());
+        }
+        let now = self.runtime.system_time();
+        let mut candidates = self.state.active_reviewers().await?;
+        candidates.retain(|reviewer| *reviewer != author);
+
+        let mut hasher = DefaultHasher::new();
+        self.state.assignment_seed.get().hash(&mut hasher);
+        self.runtime.block_height().hash(&mut hasher);
+        let mut seed = hasher.finish();
+
+        let mut assignments = HashMap::default();
+        while assignments.len() < assigned_reviewers && !candidates.is_empty() {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let index = (seed >> 33) as usize % candidates.len();
+            assignments.insert(candidates.swap_remove(index), now);
+        }
+        self.state.assignment_seed.set(seed);
+        if assignments.is_empty() {
+            return Ok(());
+        }
+        self.state
+            .update_assignments(kind, id.clone(), assignments.clone())
+            .await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::AssignReviewers {
+                kind,
+                id,
+                assignments,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
     async fn _instantiate(&mut self, argument: InstantiationArgument) -> Result<(), ReviewError> {
         self.state.instantiate_review(argument).await?;
         Ok(())
@@ -405,6 +781,17 @@ impl ReviewContract {
         Ok(())
     }
 
+    async fn _take_reviewer_exam(
+        &mut self,
+        candidate: Owner,
+        answers: Vec<ExamAnswer>,
+    ) -> Result<(), ReviewError> {
+        self.state
+            .take_reviewer_exam(candidate, answers, self.runtime.system_time())
+            .await?;
+        Ok(())
+    }
+
     async fn _update_reviewer_resume(
         &mut self,
         reviewer: Owner,
@@ -433,13 +820,16 @@ impl ReviewContract {
         if !creation_chain {
             return Ok(());
         }
-        match _reviewer {
-            Some(_reviewer) => {
-                // TODO: notify candidate is reviewer
-            }
-            _ => {
-                // TODO: notify candidate is approved
-            }
+        if _reviewer.is_some() {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                candidate,
+                NotificationKind::ReviewerApproved,
+                candidate.to_string(),
+                "You are a reviewer now".to_string(),
+            );
         }
         self.reward_credits(reviewer, Amount::from_tokens(100))
             .await?;
@@ -466,13 +856,16 @@ impl ReviewContract {
         if !creation_chain {
             return Ok(());
         }
-        match _reviewer {
-            Some(_reviewer) => {
-                // TODO: notify candidate is reviewer
-            }
-            _ => {
-                // TODO: notify candidate is approved
-            }
+        if _reviewer.is_some() {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                candidate,
+                NotificationKind::ReviewerRejected,
+                candidate.to_string(),
+                "Your reviewer application is rejected".to_string(),
+            );
         }
         self.reward_credits(reviewer, Amount::from_tokens(100))
             .await?;
@@ -490,9 +883,10 @@ impl ReviewContract {
         abbreviation: String,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        let now = self.runtime.system_time();
+        let deadline = self.state.submission_deadline(now);
         self.state
             .submit_content(Content {
-                // TODO: notify author
                 cid,
                 comment_to_cid: None,
                 title,
@@ -503,7 +897,11 @@ impl ReviewContract {
                 reviewers: HashMap::default(),
                 approved: 0,
                 rejected: 0,
-                created_at: self.runtime.system_time(),
+                status: SubmissionStatus::Pending,
+                score: 0,
+                assignments: HashMap::default(),
+                created_at: now,
+                deadline,
             })
             .await?;
         if !creation_chain {
@@ -521,6 +919,8 @@ impl ReviewContract {
         author: Owner,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        let now = self.runtime.system_time();
+        let deadline = self.state.submission_deadline(now);
         self.state
             .submit_content(Content {
                 cid,
@@ -533,7 +933,11 @@ impl ReviewContract {
                 reviewers: HashMap::default(),
                 approved: 0,
                 rejected: 0,
-                created_at: self.runtime.system_time(),
+                status: SubmissionStatus::Pending,
+                score: 0,
+                assignments: HashMap::default(),
+                created_at: now,
+                deadline,
             })
             .await?;
         if !creation_chain {
@@ -549,14 +953,18 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_eligibility(SubmissionKind::Content, content_cid.clone(), reviewer)
+            .await?;
         let content = self
             .state
             .approve_content(
                 reviewer,
                 content_cid.clone(),
                 reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
@@ -565,6 +973,7 @@ impl ReviewContract {
         }
         match content {
             Some(content) => {
+                let author = content.author;
                 match content.comment_to_cid {
                     Some(comment_to_cid) => {
                         self.comment_content(
@@ -572,6 +981,7 @@ impl ReviewContract {
                             content.cid.clone(),
                             content.content,
                             content.author,
+                            content.score,
                         )
                         .await?;
                     }
@@ -583,6 +993,7 @@ impl ReviewContract {
                             content.author,
                             content.cover,
                             content.abbreviation,
+                            content.score,
                         )
                         .await?
                     }
@@ -594,11 +1005,17 @@ impl ReviewContract {
                     }
                     _ => {}
                 }
-                // TODO: notify author content is published
-            }
-            _ => {
-                // TODO: notify author content is approved
+                let notification_app_id = self.notification_app_id();
+                notification::notify(
+                    &mut self.runtime,
+                    notification_app_id,
+                    author,
+                    NotificationKind::ContentPublished,
+                    content_cid,
+                    "Your content is approved and published".to_string(),
+                );
             }
+            _ => {}
         }
         self.reward_credits(reviewer, Amount::from_tokens(50))
             .await?;
@@ -611,27 +1028,34 @@ impl ReviewContract {
         reviewer: Owner,
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_eligibility(SubmissionKind::Content, content_cid.clone(), reviewer)
+            .await?;
         let content = self
             .state
             .reject_content(
                 reviewer,
-                content_cid,
+                content_cid.clone(),
                 reason.unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
         if !creation_chain {
             return Ok(());
         }
-        match content {
-            Some(_content) => {
-                // TODO: notify author content is rejected
-            }
-            _ => {
-                // TODO: notify author content is rejected
-            }
+        if let Some(content) = content {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                content.author,
+                NotificationKind::ContentRejected,
+                content_cid,
+                "Your content is rejected, you could appeal it".to_string(),
+            );
         }
         self.reward_credits(reviewer, Amount::from_tokens(50))
             .await?;
@@ -644,35 +1068,44 @@ impl ReviewContract {
         reviewer: Owner,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_eligibility(SubmissionKind::Asset, cid.clone(), reviewer)
+            .await?;
         let asset = self
             .state
             .approve_asset(
                 reviewer,
-                cid,
+                cid.clone(),
                 reason.unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
         if !creation_chain {
             return Ok(());
         }
-        match asset {
-            Some(asset) => {
-                self.create_collection(
-                    asset.base_uri,
-                    asset.uris,
-                    asset.price,
-                    asset.name,
-                    asset.author,
-                )
-                .await?;
-                // TODO: notify author is approved
-            }
-            _ => {
-                // TODO: notify author
-            }
+        if let Some(asset) = asset {
+            self.create_collection(
+                asset.base_uri,
+                asset.uris,
+                asset.price,
+                asset.name,
+                asset.author,
+                asset.royalty_percent,
+                asset.metadata,
+            )
+            .await?;
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                asset.author,
+                NotificationKind::AssetApproved,
+                cid,
+                "Your asset is approved and listed in market".to_string(),
+            );
         }
         self.reward_credits(reviewer, Amount::from_tokens(50))
             .await?;
@@ -685,27 +1118,34 @@ impl ReviewContract {
         reviewer: Owner,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_eligibility(SubmissionKind::Asset, cid.clone(), reviewer)
+            .await?;
         let asset = self
             .state
             .reject_asset(
                 reviewer,
-                cid,
+                cid.clone(),
                 reason.unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
         if !creation_chain {
             return Ok(());
         }
-        match asset {
-            Some(_asset) => {
-                // TODO: notify author is approved
-            }
-            _ => {
-                // TODO: notify author
-            }
+        if let Some(asset) = asset {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                asset.author,
+                NotificationKind::AssetRejected,
+                cid,
+                "Your asset is rejected, you could appeal it".to_string(),
+            );
         }
         self.reward_credits(reviewer, Amount::from_tokens(50))
             .await?;
@@ -713,6 +1153,7 @@ impl ReviewContract {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn _submit_asset(
         &mut self,
         author: Owner,
@@ -721,7 +1162,14 @@ impl ReviewContract {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
     ) -> Result<(), ReviewError> {
+        if !metadata.is_valid() {
+            return Err(ReviewError::InvalidAssetMetadata);
+        }
+        let now = self.runtime.system_time();
+        let deadline = self.state.submission_deadline(now);
         self.state
             .submit_asset(Asset {
                 cid,
@@ -730,10 +1178,16 @@ impl ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
+                metadata,
                 reviewers: HashMap::default(),
                 approved: 0,
                 rejected: 0,
-                created_at: self.runtime.system_time(),
+                status: SubmissionStatus::Pending,
+                score: 0,
+                assignments: HashMap::default(),
+                created_at: now,
+                deadline,
             })
             .await?;
         Ok(())
@@ -763,6 +1217,8 @@ impl ReviewContract {
         reason: Option<String>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_eligibility(SubmissionKind::Activity, activity_id.to_string(), owner)
+            .await?;
         let activity = self
             .state
             .approve_activity(
@@ -780,6 +1236,15 @@ impl ReviewContract {
         if let Some(activity) = activity {
             self.lock_activity_funds(activity_id, activity.budget_amount)
                 .await?;
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                activity.activity_host,
+                NotificationKind::ActivityApproved,
+                activity_id.to_string(),
+                "Your activity is approved".to_string(),
+            );
         }
         Ok(())
     }
@@ -791,7 +1256,9 @@ impl ReviewContract {
         reason: String,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
-        let _activity = self
+        self.validate_review_eligibility(SubmissionKind::Activity, activity_id.to_string(), owner)
+            .await?;
+        let activity = self
             .state
             .reject_activity(owner, activity_id, reason, self.runtime.system_time())
             .await?;
@@ -800,64 +1267,451 @@ impl ReviewContract {
         }
         self.reward_credits(owner, Amount::from_tokens(50)).await?;
         self.reward_tokens().await?;
-        Ok(())
-    }
-
-    fn require_message_id(&mut self) -> Result<MessageId, ReviewError> {
-        match self.runtime.message_id() {
-            Some(message_id) => Ok(message_id),
-            None => Err(ReviewError::InvalidMessageId),
+        if let Some(activity) = activity {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                activity.activity_host,
+                NotificationKind::ActivityRejected,
+                activity_id.to_string(),
+                "Your activity is rejected".to_string(),
+            );
         }
+        Ok(())
     }
 
-    fn require_authenticated_signer(&mut self) -> Result<Owner, ReviewError> {
-        match self.runtime.authenticated_signer() {
-            Some(owner) => Ok(owner),
-            None => Err(ReviewError::InvalidSigner),
+    async fn queue_report(&mut self, report: ContentReport) -> Result<(), ReviewError> {
+        if self.state.report_exists(report.cid.clone()).await? {
+            return Ok(());
         }
-    }
-
-    fn on_op_apply_reviewer(&mut self, resume: String) -> Result<ReviewResponse, ReviewError> {
+        self.state.submit_report(report.clone()).await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::ApplyReviewer { resume })
+            .prepare_message(Message::SubmitReport { report })
             .with_authentication()
-            .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(ReviewResponse::Ok)
+            .send_to(dest);
+        Ok(())
     }
 
-    fn on_op_upeate_reviewer_resume(
-        &mut self,
-        resume: String,
-    ) -> Result<ReviewResponse, ReviewError> {
-        self.runtime
-            .prepare_message(Message::UpdateReviewerResume { resume })
-            .with_authentication()
-            .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(ReviewResponse::Ok)
+    // Reports queued in feed before they were filed through review enter the queue when the
+    // first reviewer judges it
+    async fn import_report(&mut self, cid: String) -> Result<(), ReviewError> {
+        if self.state.report_exists(cid.clone()).await? {
+            return Ok(());
+        }
+        let report = self.reported_content(cid).await?;
+        self.queue_report(report).await
     }
 
-    fn on_op_approve_reviewer(
+    async fn _review_report(
         &mut self,
-        candidate: Owner,
+        reviewer: Owner,
+        cid: String,
+        upheld: bool,
         reason: Option<String>,
-    ) -> Result<ReviewResponse, ReviewError> {
-        self.runtime
-            .prepare_message(Message::ApproveReviewer { candidate, reason })
-            .with_authentication()
-            .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(ReviewResponse::Ok)
+        creation_chain: bool,
+    ) -> Result<(), ReviewError> {
+        if creation_chain {
+            self.import_report(cid.clone()).await?;
+            let author = self.state.report_author(cid.clone()).await?;
+            self.validate_conflict_of_interest(reviewer, author).await?;
+        }
+        let report = self
+            .state
+            .review_report(
+                reviewer,
+                cid.clone(),
+                upheld,
+                reason.unwrap_or_default(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        let report = match report {
+            Some(report) => report,
+            None => return Ok(()),
+        };
+        self.resolve_report(cid, upheld).await?;
+        for reporter in report.reporters {
+            if upheld {
+                self.reward_credits(reporter, Amount::from_tokens(50))
+                    .await?;
+            } else {
+                self.penalize_credits(reporter, Amount::from_tokens(50))
+                    .await?;
+            }
+        }
+        for (voter, review) in report.reviewers {
+            if review.approved == upheld {
+                self.reward_credits(voter, Amount::from_tokens(50)).await?;
+            } else {
+                self.penalize_credits(voter, Amount::from_tokens(20))
+                    .await?;
+            }
+        }
+        Ok(())
     }
 
-    fn on_op_reject_reviewer(
+    async fn _appeal(
         &mut self,
-        candidate: Owner,
-        reason: Option<String>,
-    ) -> Result<ReviewResponse, ReviewError> {
-        self.runtime
-            .prepare_message(Message::RejectReviewer { candidate, reason })
-            .with_authentication()
-            .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(ReviewResponse::Ok)
+        author: Owner,
+        cid: String,
+        statement: String,
+        creation_chain: bool,
+    ) -> Result<(), ReviewError> {
+        // Bond is held by review escrow until the appeal is resolved. Credit applies the
+        // transfer in this transaction, so the appeal is only recorded once it is escrowed
+        if creation_chain {
+            let bond = *self.state.appeal_bond.get();
+            if self.spendable_credits(author)? < bond {
+                return Err(ReviewError::InsufficientCredits);
+            }
+            let escrow = self.escrow_owner();
+            self.transfer_credits(author, escrow, bond).await?;
+        }
+        self.state
+            .submit_appeal(author, cid, statement, self.runtime.system_time())
+            .await?;
+        Ok(())
+    }
+
+    async fn _review_appeal(
+        &mut self,
+        reviewer: Owner,
+        cid: String,
+        approved: bool,
+        reason: Option<String>,
+        creation_chain: bool,
+    ) -> Result<(), ReviewError> {
+        if creation_chain {
+            let author = match self.state.appeals.get(&cid).await? {
+                Some(appeal) => appeal.author,
+                None => return Err(ReviewError::InvalidAppeal),
+            };
+            self.validate_conflict_of_interest(reviewer, author).await?;
+        }
+        let appeal = self
+            .state
+            .review_appeal(
+                reviewer,
+                cid.clone(),
+                approved,
+                reason.unwrap_or_default(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        self.reward_credits(reviewer, Amount::from_tokens(50))
+            .await?;
+        self.reward_tokens().await?;
+        let appeal = match appeal {
+            Some(appeal) => appeal,
+            None => return Ok(()),
+        };
+        let escrow = self.escrow_owner();
+        if !approved {
+            self.penalize_credits(escrow, appeal.bond).await?;
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                appeal.author,
+                NotificationKind::AppealResolved,
+                cid,
+                "Your appeal is rejected, the bond is forfeited".to_string(),
+            );
+            return Ok(());
+        }
+        self.transfer_credits(escrow, appeal.author, appeal.bond)
+            .await?;
+        match appeal.kind {
+            SubmissionKind::Asset => {
+                if let Some(asset) = self.state.asset_applications.get(&cid).await? {
+                    self.create_collection(
+                        asset.base_uri,
+                        asset.uris,
+                        asset.price,
+                        asset.name,
+                        asset.author,
+                        asset.royalty_percent,
+                        asset.metadata,
+                    )
+                    .await?;
+                }
+            }
+            _ => {
+                if let Some(content) = self.state.content_applications.get(&cid).await? {
+                    match content.comment_to_cid {
+                        Some(comment_to_cid) => {
+                            self.comment_content(
+                                comment_to_cid,
+                                content.cid,
+                                content.content,
+                                content.author,
+                                content.score,
+                            )
+                            .await?
+                        }
+                        _ => {
+                            self.publish_content(
+                                content.cid,
+                                content.title,
+                                content.content,
+                                content.author,
+                                content.cover,
+                                content.abbreviation,
+                                content.score,
+                            )
+                            .await?
+                        }
+                    }
+                }
+            }
+        }
+        let notification_app_id = self.notification_app_id();
+        notification::notify(
+            &mut self.runtime,
+            notification_app_id,
+            appeal.author,
+            NotificationKind::AppealResolved,
+            cid,
+            "Your appeal is approved, the bond is refunded".to_string(),
+        );
+        Ok(())
+    }
+
+    async fn _remove_reviewer(
+        &mut self,
+        reviewer: Owner,
+        candidate: Owner,
+        reason: Option<String>,
+        creation_chain: bool,
+    ) -> Result<(), ReviewError> {
+        let removed = self
+            .state
+            .remove_reviewer(
+                reviewer,
+                candidate,
+                reason.unwrap_or_default(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        if let Some(_reviewer) = removed {
+            self.penalize_credits(candidate, Amount::from_tokens(100))
+                .await?;
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                candidate,
+                NotificationKind::ReviewerSuspended,
+                candidate.to_string(),
+                "You are removed from reviewers by votes".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    async fn _resume_reviewer(
+        &mut self,
+        reviewer: Owner,
+        candidate: Owner,
+        reason: Option<String>,
+        creation_chain: bool,
+    ) -> Result<(), ReviewError> {
+        let resumed = self
+            .state
+            .resume_reviewer(
+                reviewer,
+                candidate,
+                reason.unwrap_or_default(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        if let Some(_reviewer) = resumed {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                candidate,
+                NotificationKind::ReviewerApproved,
+                candidate.to_string(),
+                "You are resumed as reviewer by votes".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    // Inactive reviewers are only judged on creation chain then synced to subscribers
+    async fn suspend_inactive_reviewers(
+        &mut self,
+        candidates: Vec<Owner>,
+    ) -> Result<(), ReviewError> {
+        let reviewers = self
+            .state
+            .inactive_reviewers(candidates, self.runtime.system_time())
+            .await?;
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+        let reviewers = self.state.suspend_reviewers(reviewers).await?;
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+        for reviewer in reviewers.clone() {
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                reviewer,
+                NotificationKind::ReviewerSuspended,
+                reviewer.to_string(),
+                "You are suspended for inactivity, other reviewers could vote to resume you"
+                    .to_string(),
+            );
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SuspendReviewers { reviewers })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    // Stale submissions are only judged on creation chain then synced to subscribers
+    async fn expire_submissions(
+        &mut self,
+        submissions: Vec<PendingSubmission>,
+    ) -> Result<(), ReviewError> {
+        let now = self.runtime.system_time();
+        for submission in submissions.clone() {
+            let appeal = self
+                .state
+                .expire_submission(submission.kind, submission.id.clone(), now)
+                .await?;
+            if let Some(appeal) = appeal {
+                let escrow = self.escrow_owner();
+                self.transfer_credits(escrow, appeal.author, appeal.bond)
+                    .await?;
+                let notification_app_id = self.notification_app_id();
+                notification::notify(
+                    &mut self.runtime,
+                    notification_app_id,
+                    appeal.author,
+                    NotificationKind::AppealResolved,
+                    appeal.cid,
+                    "Your appeal is expired without enough reviews, the bond is refunded"
+                        .to_string(),
+                );
+                continue;
+            }
+            let notification_app_id = self.notification_app_id();
+            notification::notify(
+                &mut self.runtime,
+                notification_app_id,
+                submission.author,
+                NotificationKind::SubmissionExpired,
+                submission.id,
+                format!(
+                    "Your {:?} submission is expired without enough reviews",
+                    submission.kind
+                ),
+            );
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ExpireSubmissions { submissions })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    // Submissions are expired lazily when they are touched on creation chain, return true if the
+    // submission is expired so the touch is skipped
+    async fn expire_stale_submission(
+        &mut self,
+        kinds: &[SubmissionKind],
+        id: &str,
+    ) -> Result<bool, ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(false);
+        }
+        let now = self.runtime.system_time();
+        match self.state.stale_submission(kinds, id, now).await? {
+            Some(submission) => {
+                self.expire_submissions(vec![submission]).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn require_message_id(&mut self) -> Result<MessageId, ReviewError> {
+        match self.runtime.message_id() {
+            Some(message_id) => Ok(message_id),
+            None => Err(ReviewError::InvalidMessageId),
+        }
+    }
+
+    fn require_authenticated_signer(&mut self) -> Result<Owner, ReviewError> {
+        match self.runtime.authenticated_signer() {
+            Some(owner) => Ok(owner),
+            None => Err(ReviewError::InvalidSigner),
+        }
+    }
+
+    fn on_op_apply_reviewer(&mut self, resume: String) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ApplyReviewer { resume })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_upeate_reviewer_resume(
+        &mut self,
+        resume: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::UpdateReviewerResume { resume })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_approve_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ApproveReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_reject_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::RejectReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
     }
 
     fn on_op_submit_content(
@@ -886,12 +1740,18 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::ApproveContent {
                 content_cid,
                 reason_cid,
                 reason,
+                scores: scores
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -902,11 +1762,17 @@ impl ReviewContract {
         &mut self,
         content_cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::RejectContent {
                 content_cid,
                 reason,
+                scores: scores
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -934,9 +1800,18 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::ApproveAsset { cid, reason })
+            .prepare_message(Message::ApproveAsset {
+                cid,
+                reason,
+                scores: scores
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
@@ -946,14 +1821,24 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::RejectAsset { cid, reason })
+            .prepare_message(Message::RejectAsset {
+                cid,
+                reason,
+                scores: scores
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn on_op_submit_asset(
         &mut self,
         cid: String,
@@ -961,6 +1846,8 @@ impl ReviewContract {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
+        metadata: Option<CollectionMetadata>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::SubmitAsset {
@@ -969,6 +1856,8 @@ impl ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent: royalty_percent.unwrap_or_default(),
+                metadata: metadata.unwrap_or_default(),
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -1029,7 +1918,162 @@ impl ReviewContract {
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        // TODO: send initialization argument to subscriber
+        // TODO: send initialization argument to subscriber
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_report(
+        &mut self,
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::Report {
+                cid,
+                reason_category,
+                note,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_uphold_report(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::UpholdReport { cid, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_dismiss_report(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::DismissReport { cid, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_remove_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::RemoveReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_resume_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ResumeReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_suspend_inactive_reviewers(
+        &mut self,
+        reviewers: Vec<Owner>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::SuspendInactiveReviewers { reviewers })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_update_quorum_rule(
+        &mut self,
+        kind: SubmissionKind,
+        rule: QuorumRuleInput,
+    ) -> Result<ReviewResponse, ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::UpdateQuorumRule {
+                kind,
+                rule: rule.into(),
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_appeal(
+        &mut self,
+        cid: String,
+        statement: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::Appeal { cid, statement })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_approve_appeal(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ApproveAppeal { cid, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_reject_appeal(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::RejectAppeal { cid, reason })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_expire_submission(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ExpireSubmission { kind, id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_take_reviewer_exam(
+        &mut self,
+        answers: Vec<ExamAnswerInput>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::TakeReviewerExam {
+                answers: answers.into_iter().map(Into::into).collect(),
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
     }
 
@@ -1109,7 +2153,7 @@ impl ReviewContract {
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._approve_reviewer(reviewer, candidate, reason.clone(), creation_chain)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
@@ -1130,7 +2174,7 @@ impl ReviewContract {
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._reject_reviewer(reviewer, candidate, reason.clone(), creation_chain)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
@@ -1154,7 +2198,7 @@ impl ReviewContract {
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._submit_content(
             cid.clone(),
             title.clone(),
@@ -1171,7 +2215,7 @@ impl ReviewContract {
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
             .prepare_message(Message::SubmitContent {
-                cid,
+                cid: cid.clone(),
                 title,
                 content,
                 cover,
@@ -1179,6 +2223,8 @@ impl ReviewContract {
             })
             .with_authentication()
             .send_to(dest);
+        self.assign_reviewers(SubmissionKind::Content, cid, author)
+            .await?;
         Ok(())
     }
 
@@ -1187,15 +2233,26 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(
+                &[SubmissionKind::Content, SubmissionKind::Comment],
+                &content_cid,
+            )
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._approve_content(
             reviewer,
             content_cid.clone(),
             reason_cid.clone(),
             reason.clone(),
+            scores.clone(),
             creation_chain,
         )
         .await?;
@@ -1208,6 +2265,7 @@ impl ReviewContract {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1218,14 +2276,25 @@ impl ReviewContract {
         &mut self,
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(
+                &[SubmissionKind::Content, SubmissionKind::Comment],
+                &content_cid,
+            )
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._reject_content(
             reviewer,
             content_cid.clone(),
             reason.clone(),
+            scores.clone(),
             creation_chain,
         )
         .await?;
@@ -1237,6 +2306,7 @@ impl ReviewContract {
             .prepare_message(Message::RejectContent {
                 content_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1251,7 +2321,7 @@ impl ReviewContract {
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._submit_comment(
             comment_cid.clone(),
             cid.clone(),
@@ -1267,11 +2337,13 @@ impl ReviewContract {
         self.runtime
             .prepare_message(Message::SubmitComment {
                 cid,
-                comment_cid,
+                comment_cid: comment_cid.clone(),
                 comment,
             })
             .with_authentication()
             .send_to(dest);
+        self.assign_reviewers(SubmissionKind::Comment, comment_cid, author)
+            .await?;
         Ok(())
     }
 
@@ -1279,18 +2351,35 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(&[SubmissionKind::Asset], &cid)
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._approve_asset(reviewer, cid.clone(), reason.clone(), creation_chain)
-            .await?;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._approve_asset(
+            reviewer,
+            cid.clone(),
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::ApproveAsset { cid, reason })
+            .prepare_message(Message::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -1300,23 +2389,41 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(&[SubmissionKind::Asset], &cid)
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._reject_asset(reviewer, cid.clone(), reason.clone(), creation_chain)
-            .await?;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._reject_asset(
+            reviewer,
+            cid.clone(),
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::RejectAsset { cid, reason })
+            .prepare_message(Message::RejectAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn on_msg_submit_asset(
         &mut self,
         cid: String,
@@ -1324,6 +2431,8 @@ impl ReviewContract {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
         self._submit_asset(
@@ -1333,6 +2442,8 @@ impl ReviewContract {
             uris.clone(),
             price.clone(),
             name.clone(),
+            royalty_percent,
+            metadata.clone(),
         )
         .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
@@ -1341,14 +2452,18 @@ impl ReviewContract {
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
             .prepare_message(Message::SubmitAsset {
-                cid,
+                cid: cid.clone(),
                 base_uri,
                 uris,
                 price,
                 name,
+                royalty_percent,
+                metadata,
             })
             .with_authentication()
             .send_to(dest);
+        self.assign_reviewers(SubmissionKind::Asset, cid, author)
+            .await?;
         Ok(())
     }
 
@@ -1390,8 +2505,14 @@ impl ReviewContract {
         reason: Option<String>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(&[SubmissionKind::Activity], &activity_id.to_string())
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._approve_activity(reviewer, activity_id, reason.clone(), creation_chain)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
@@ -1414,8 +2535,14 @@ impl ReviewContract {
         reason: String,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
+        if self
+            .expire_stale_submission(&[SubmissionKind::Activity], &activity_id.to_string())
+            .await?
+        {
+            return Ok(());
+        }
         let creation_chain =
-            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self._reject_activity(reviewer, activity_id, reason.clone(), creation_chain)
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
@@ -1452,6 +2579,512 @@ impl ReviewContract {
             })
             .with_authentication()
             .send_to(dest);
+        self.assign_reviewers(
+            SubmissionKind::Activity,
+            activity_id.to_string(),
+            activity_host,
+        )
+        .await?;
+        Ok(())
+    }
+
+    // Feed and review share the creation chain, so the report is filed to feed and pushed to the
+    // review queue in the same message
+    async fn on_msg_report(
+        &mut self,
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        if let Some(report) = self.file_report(cid, reason_category, note).await? {
+            self.queue_report(report).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_msg_submit_report(&mut self, report: ContentReport) -> Result<(), ReviewError> {
+        // Only creation chain could import report from feed
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::InvalidReport);
+        }
+        self.state.submit_report(report).await
+    }
+
+    async fn on_msg_uphold_report(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._review_report(reviewer, cid.clone(), true, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::UpholdReport { cid, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_dismiss_report(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._review_report(reviewer, cid.clone(), false, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::DismissReport { cid, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_remove_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._remove_reviewer(reviewer, candidate, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::RemoveReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_resume_reviewer(
+        &mut self,
+        candidate: Owner,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._resume_reviewer(reviewer, candidate, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ResumeReviewer { candidate, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_suspend_inactive_reviewers(
+        &mut self,
+        reviewers: Vec<Owner>,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        self.suspend_inactive_reviewers(reviewers).await
+    }
+
+    async fn on_msg_suspend_reviewers(&mut self, reviewers: Vec<Owner>) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        self.state.suspend_reviewers(reviewers).await?;
+        Ok(())
+    }
+
+    async fn on_msg_update_quorum_rule(
+        &mut self,
+        kind: SubmissionKind,
+        rule: QuorumRule,
+    ) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        self.state.update_quorum_rule(kind, rule.clone()).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::UpdateQuorumRule { kind, rule })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_assign_reviewers(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        assignments: HashMap<Owner, Timestamp>,
+    ) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::NotAssigned);
+        }
+        self.state.update_assignments(kind, id, assignments).await
+    }
+
+    async fn on_msg_expire_submissions(
+        &mut self,
+        submissions: Vec<PendingSubmission>,
+    ) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        let now = self.runtime.system_time();
+        for submission in submissions {
+            self.state
+                .expire_submission(submission.kind, submission.id, now)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn on_msg_expire_submission(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::OperationNotAllowed);
+        }
+        if !self.expire_stale_submission(&[kind], &id).await? {
+            return Err(ReviewError::NotExpired);
+        }
+        Ok(())
+    }
+
+    async fn on_msg_appeal(&mut self, cid: String, statement: String) -> Result<(), ReviewError> {
+        let author = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._appeal(author, cid.clone(), statement.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Appeal { cid, statement })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_approve_appeal(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        if self.expire_stale_submission(APPEAL_KINDS, &cid).await? {
+            return Ok(());
+        }
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._review_appeal(reviewer, cid.clone(), true, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ApproveAppeal { cid, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_reject_appeal(
+        &mut self,
+        cid: String,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        if self.expire_stale_submission(APPEAL_KINDS, &cid).await? {
+            return Ok(());
+        }
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        self._review_appeal(reviewer, cid.clone(), false, reason.clone(), creation_chain)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::RejectAppeal { cid, reason })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_take_reviewer_exam(
+        &mut self,
+        answers: Vec<ExamAnswer>,
+    ) -> Result<(), ReviewError> {
+        let candidate = self.require_authenticated_signer()?;
+        self._take_reviewer_exam(candidate, answers.clone()).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::TakeReviewerExam { answers })
+            .with_authentication()
+            .send_to(dest);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use linera_sdk::{
+        base::{Amount, ChainId, Owner, Timestamp},
+        util::BlockingWait,
+        views::View,
+        ContractRuntime,
+    };
+    use review::{
+        ContentReport, InstantiationArgument, QuorumKind, QuorumRule, ReviewError, Reviewer,
+        SubmissionKind,
+    };
+
+    use super::{state::Review, ReviewContract};
+
+    fn owner(id: u8) -> Owner {
+        format!("{:064x}", id).parse().unwrap()
+    }
+
+    /// Review with owner 1 as genesis reviewer, joined by the given reviewers
+    fn create_review(reviewers: &[Owner]) -> Review {
+        let runtime = ContractRuntime::<ReviewContract>::new();
+        let mut review = Review::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        review
+            .instantiate_review(InstantiationArgument {
+                content_quorum: QuorumRule {
+                    kind: QuorumKind::Percent,
+                    approved: 50,
+                    rejected: 30,
+                    minimum: 2,
+                },
+                comment_quorum: QuorumRule::default(),
+                asset_quorum: QuorumRule::default(),
+                activity_quorum: QuorumRule::default(),
+                reviewer_quorum: QuorumRule::default(),
+                report_upheld_threshold: 2,
+                report_dismissed_threshold: 2,
+                reviewer_inactive_days: 0,
+                conflict_of_interest_days: 0,
+                assigned_reviewers: 0,
+                assignment_timeout_ms: 0,
+                submission_timeout_ms: 0,
+                rubrics: Vec::new(),
+                appeal_quorum: QuorumRule::default(),
+                appeal_bond: Amount::ZERO,
+            })
+            .blocking_wait()
+            .unwrap();
+        review
+            .genesis_reviewer(ChainId::root(0), owner(1), Timestamp::from(0))
+            .blocking_wait()
+            .unwrap();
+        let genesis = review
+            .reviewers
+            .get(&owner(1))
+            .blocking_wait()
+            .unwrap()
+            .unwrap();
+        for reviewer in reviewers {
+            review
+                .add_exist_reviewer(Reviewer {
+                    reviewer: *reviewer,
+                    ..genesis.clone()
+                })
+                .blocking_wait()
+                .unwrap();
+        }
+        review.reviewer_number.set(reviewers.len() as u16 + 1);
+        review
+    }
+
+    fn quorum(review: &Review, kind: SubmissionKind) -> (u16, u16) {
+        (
+            review.quorum(kind, true).blocking_wait().unwrap(),
+            review.quorum(kind, false).blocking_wait().unwrap(),
+        )
+    }
+
+    #[test]
+    fn quorum_rules() {
+        let mut review = create_review(&[]);
+        // Never more votes than active reviewers
+        assert_eq!(quorum(&review, SubmissionKind::Content), (1, 1));
+
+        // Percent rounds up and is raised to the minimum
+        review.reviewer_number.set(3);
+        assert_eq!(quorum(&review, SubmissionKind::Content), (2, 2));
+        review.reviewer_number.set(10);
+        assert_eq!(quorum(&review, SubmissionKind::Content), (5, 3));
+        // At least one vote, even with an empty rule
+        assert_eq!(quorum(&review, SubmissionKind::Asset), (1, 1));
+
+        review
+            .update_quorum_rule(
+                SubmissionKind::Content,
+                QuorumRule {
+                    kind: QuorumKind::Absolute,
+                    approved: 7,
+                    rejected: 4,
+                    minimum: 0,
+                },
+            )
+            .blocking_wait()
+            .unwrap();
+        assert_eq!(quorum(&review, SubmissionKind::Content), (7, 4));
+        review.reviewer_number.set(5);
+        assert_eq!(quorum(&review, SubmissionKind::Content), (5, 4));
+        review.reviewer_number.set(0);
+        assert_eq!(quorum(&review, SubmissionKind::Content), (1, 1));
+    }
+
+    fn submit_report(review: &mut Review, cid: &str, author: Owner, reporter: Owner) {
+        review
+            .submit_report(ContentReport {
+                cid: cid.to_string(),
+                author,
+                reporters: vec![reporter],
+                reviewers: HashMap::new(),
+                upheld: 0,
+                dismissed: 0,
+                created_at: Timestamp::from(0),
+            })
+            .blocking_wait()
+            .unwrap();
+    }
+
+    fn review_report(
+        review: &mut Review,
+        reviewer: Owner,
+        cid: &str,
+        upheld: bool,
+    ) -> Result<Option<ContentReport>, ReviewError> {
+        review
+            .review_report(
+                reviewer,
+                cid.to_string(),
+                upheld,
+                String::new(),
+                Timestamp::from(1000),
+            )
+            .blocking_wait()
+    }
+
+    #[test]
+    fn report_queue() {
+        let (alice, bob, carol, dave, erin) = (owner(1), owner(2), owner(3), owner(4), owner(5));
+        let mut review = create_review(&[bob, carol, dave]);
+        submit_report(&mut review, "content", dave, erin);
+        assert!(matches!(
+            review
+                .submit_report(ContentReport {
+                    cid: "content".to_string(),
+                    author: dave,
+                    reporters: vec![bob],
+                    reviewers: HashMap::new(),
+                    upheld: 0,
+                    dismissed: 0,
+                    created_at: Timestamp::from(0),
+                })
+                .blocking_wait(),
+            Err(ReviewError::AlreadyExists)
+        ));
+
+        // Neither author, reporters nor anyone outside reviewers could judge the report
+        assert!(matches!(
+            review_report(&mut review, dave, "content", false),
+            Err(ReviewError::InvalidReviewer)
+        ));
+        assert!(matches!(
+            review_report(&mut review, erin, "content", true),
+            Err(ReviewError::InvalidReviewer)
+        ));
+        assert_eq!(
+            review_report(&mut review, bob, "content", true).unwrap(),
+            None
+        );
+        assert!(matches!(
+            review_report(&mut review, bob, "content", true),
+            Err(ReviewError::AlreadyReviewed)
+        ));
+        assert_eq!(
+            review_report(&mut review, carol, "content", false).unwrap(),
+            None
+        );
+        let report = review_report(&mut review, alice, "content", true)
+            .unwrap()
+            .expect("Report should be upheld at threshold");
+        assert_eq!((report.upheld, report.dismissed), (2, 1));
+        assert!(!review
+            .report_exists("content".to_string())
+            .blocking_wait()
+            .unwrap());
+        assert!(matches!(
+            review_report(&mut review, dave, "content", true),
+            Err(ReviewError::InvalidReport)
+        ));
+
+        // Reviewers agreeing with the outcome are credited
+        let reputation = |review: &Review, reviewer: Owner| {
+            review
+                .reviewers
+                .get(&reviewer)
+                .blocking_wait()
+                .unwrap()
+                .unwrap()
+                .reputation
+        };
+        assert_eq!(reputation(&review, bob).agreed, 1);
+        assert_eq!(reputation(&review, carol).disagreed, 1);
+
+        // Threshold larger than reviewers resolves once every reviewer agrees
+        review.report_dismissed_threshold.set(10);
+        review.reviewer_number.set(2);
+        submit_report(&mut review, "other", erin, dave);
+        assert_eq!(
+            review_report(&mut review, bob, "other", false).unwrap(),
+            None
+        );
+        let report = review_report(&mut review, carol, "other", false)
+            .unwrap()
+            .expect("Report should be dismissed by every reviewer");
+        assert_eq!(report.dismissed, 2);
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, InputObject, Request, Response, SimpleObject};
+use feed::ReportReason;
 use linera_sdk::{
     base::{
         Amount, ApplicationId, ArithmeticError, ChainId, ContractAbi, Owner, ServiceAbi, Timestamp,
     },
     graphql::GraphQLMutationRoot,
 };
+use market::CollectionMetadata;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -35,18 +37,176 @@ pub struct ReviewParameters {
     pub credit_app_id: ApplicationId<credit::CreditAbi>,
     pub foundation_app_id: ApplicationId<foundation::FoundationAbi>,
     pub market_app_id: ApplicationId<market::MarketAbi>,
+    pub notification_app_id: ApplicationId<notification::NotificationAbi>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, PartialOrd)]
+pub enum SubmissionKind {
+    Content,
+    Comment,
+    Asset,
+    Activity,
+    Reviewer,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, PartialOrd, Default)]
+pub enum QuorumKind {
+    #[default]
+    Absolute,
+    /// Percent of active reviewers
+    Percent,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, InputObject)]
+pub struct QuorumRuleInput {
+    pub kind: QuorumKind,
+    pub approved: u16,
+    pub rejected: u16,
+    pub minimum: u16,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Eq, PartialEq, PartialOrd, SimpleObject, Default,
+)]
+pub struct QuorumRule {
+    pub kind: QuorumKind,
+    pub approved: u16,
+    pub rejected: u16,
+    pub minimum: u16,
+}
+
+impl From<QuorumRuleInput> for QuorumRule {
+    fn from(rule: QuorumRuleInput) -> Self {
+        QuorumRule {
+            kind: rule.kind,
+            approved: rule.approved,
+            rejected: rule.rejected,
+            minimum: rule.minimum,
+        }
+    }
+}
+
+impl QuorumRule {
+    /// Votes needed to approve or reject, never more than active reviewers
+    pub fn quorum(&self, approved: bool, active_reviewers: u16) -> u16 {
+        let value = match approved {
+            true => self.approved as u32,
+            false => self.rejected as u32,
+        };
+        let required = match self.kind {
+            QuorumKind::Absolute => value,
+            QuorumKind::Percent => (active_reviewers as u32 * value + 99) / 100,
+        };
+        required
+            .max(self.minimum as u32)
+            .min(active_reviewers as u32)
+            .max(1) as u16
+    }
+}
+
+/// Criteria of a submission kind, each criterion is scored 1 - 5 by reviewers
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, PartialOrd, SimpleObject)]
+pub struct Rubric {
+    pub kind: SubmissionKind,
+    pub criteria: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, InputObject)]
+pub struct RubricScoreInput {
+    pub criterion: String,
+    pub score: u8,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, SimpleObject)]
+pub struct RubricScore {
+    pub criterion: String,
+    pub score: u8,
+}
+
+impl From<RubricScoreInput> for RubricScore {
+    fn from(score: RubricScoreInput) -> Self {
+        RubricScore {
+            criterion: score.criterion,
+            score: score.score,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, InputObject)]
+pub struct ExamAnswerInput {
+    /// Index of the question in the exam of the applicant
+    pub question: u16,
+    pub approved: bool,
+    pub scores: Option<Vec<RubricScoreInput>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, SimpleObject)]
+pub struct ExamAnswer {
+    pub question: u16,
+    pub approved: bool,
+    pub scores: Vec<RubricScore>,
+}
+
+impl From<ExamAnswerInput> for ExamAnswer {
+    fn from(answer: ExamAnswerInput) -> Self {
+        ExamAnswer {
+            question: answer.question,
+            approved: answer.approved,
+            scores: answer
+                .scores
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+/// Resolved content given to a reviewer applicant, its cid and consensus stay hidden until graded
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, SimpleObject)]
+pub struct ExamQuestion {
+    pub question: u16,
+    pub title: String,
+    pub content: String,
+    pub cover: String,
+    pub abbreviation: String,
+}
+
+/// Applicant grades of reviewed contents compared with the consensus of reviewers
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, SimpleObject)]
+pub struct ReviewerExam {
+    /// Cids of the exam questions, revealed once the exam is graded
+    pub questions: Vec<String>,
+    pub answers: Vec<ExamAnswer>,
+    /// 0 - 100, agreement of decisions and rubric scores with the consensus
+    pub agreement: u16,
+    pub taken_at: Timestamp,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
 pub struct InstantiationArgument {
-    pub content_approved_threshold: u16,
-    pub content_rejected_threshold: u16,
-    pub asset_approved_threshold: u16,
-    pub asset_rejected_threshold: u16,
-    pub reviewer_approved_threshold: u16,
-    pub reviewer_rejected_threshold: u16,
-    pub activity_approved_threshold: u16,
-    pub activity_rejected_threshold: u16,
+    pub content_quorum: QuorumRule,
+    pub comment_quorum: QuorumRule,
+    pub asset_quorum: QuorumRule,
+    pub activity_quorum: QuorumRule,
+    pub reviewer_quorum: QuorumRule,
+    pub report_upheld_threshold: u16,
+    pub report_dismissed_threshold: u16,
+    /// Reviewer without any review in these days will be suspended, 0 to disable
+    pub reviewer_inactive_days: u16,
+    /// Reviewer tipped or followed the author in these days could not review, 0 to disable
+    pub conflict_of_interest_days: u16,
+    /// Randomly assigned reviewers of each submission, 0 to let anyone review
+    pub assigned_reviewers: u16,
+    /// Slot of assigned reviewer who doesn't review in time is open to other reviewers
+    pub assignment_timeout_ms: u64,
+    /// Pending submissions and appeals expire after this duration, 0 to disable
+    pub submission_timeout_ms: u64,
+    pub rubrics: Vec<Rubric>,
+    /// Quorum of appeal round, always larger than the quorum of the first round
+    pub appeal_quorum: QuorumRule,
+    /// Credits locked by the author to appeal, refunded if the rejection is overturned
+    pub appeal_bond: Amount,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -54,9 +214,101 @@ pub struct Review {
     pub reviewer: Owner,
     pub approved: bool,
     pub reason: String,
+    pub scores: Vec<RubricScore>,
     pub created_at: Timestamp,
 }
 
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, Default)]
+pub enum SubmissionStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+    Appealed,
+}
+
+/// Pending submission index entry, removed once the submission is resolved or expired
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct PendingSubmission {
+    pub kind: SubmissionKind,
+    pub id: String,
+    pub author: Owner,
+    pub created_at: Timestamp,
+    pub deadline: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct SubmissionKey {
+    pub kind: SubmissionKind,
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct StatusChange {
+    pub status: SubmissionStatus,
+    pub changed_at: Timestamp,
+}
+
+/// Lifecycle of a submission for its author, built from current review state
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct SubmissionRecord {
+    pub kind: SubmissionKind,
+    pub id: String,
+    pub status: SubmissionStatus,
+    pub reviews: Vec<Review>,
+    pub appeal: Option<Appeal>,
+    pub created_at: Timestamp,
+    /// Status changes in order, the first one is the submission itself
+    pub status_changes: Vec<StatusChange>,
+    pub updated_at: Timestamp,
+    /// Deadline of reviewing, None for reviewer applications
+    pub deadline: Option<Timestamp>,
+}
+
+const REPUTATION_LATENCY_MS: u64 = 7 * 24 * 3600 * 1000;
+const REPUTATION_ACTIVE_REVIEWS: u64 = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq, Default)]
+pub struct Reputation {
+    /// 0 - 100, 60 from agreement with final outcomes, 20 from latency, 20 from activity
+    pub score: u16,
+    pub reviewed: u64,
+    pub agreed: u64,
+    pub disagreed: u64,
+    pub total_latency_ms: u64,
+    pub last_active_at: Timestamp,
+}
+
+impl Reputation {
+    pub fn new(now: Timestamp) -> Self {
+        let mut reputation = Reputation {
+            last_active_at: now,
+            ..Default::default()
+        };
+        reputation.refresh();
+        reputation
+    }
+
+    pub fn refresh(&mut self) {
+        let settled = self.agreed + self.disagreed;
+        let agreement = match settled {
+            0 => 30,
+            _ => self.agreed * 60 / settled,
+        };
+        let latency = match self.reviewed {
+            0 => 10,
+            _ => {
+                let average = self.total_latency_ms / self.reviewed;
+                REPUTATION_LATENCY_MS.saturating_sub(average) * 20 / REPUTATION_LATENCY_MS
+            }
+        };
+        let activity =
+            self.reviewed.min(REPUTATION_ACTIVE_REVIEWS) * 20 / REPUTATION_ACTIVE_REVIEWS;
+        self.score = (agreement + latency + activity) as u16;
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
 pub struct Reviewer {
     pub chain_id: ChainId,
@@ -66,6 +318,13 @@ pub struct Reviewer {
     pub approved: u16,
     pub rejected: u16,
     pub created_at: Timestamp,
+    pub reputation: Reputation,
+    pub suspended: bool,
+    /// Votes of other reviewers to remove this reviewer
+    pub removals: HashMap<Owner, Review>,
+    /// Votes of other reviewers to resume this reviewer after suspension
+    pub resumptions: HashMap<Owner, Review>,
+    pub exam: Option<ReviewerExam>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -81,7 +340,12 @@ pub struct Content {
     pub reviewers: HashMap<Owner, Review>,
     pub approved: u16,
     pub rejected: u16,
+    pub status: SubmissionStatus,
+    /// Aggregated rubric score when approved, 100 - 500 for average 1.00 - 5.00
+    pub score: u16,
+    pub assignments: HashMap<Owner, Timestamp>,
     pub created_at: Timestamp,
+    pub deadline: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -92,10 +356,17 @@ pub struct Asset {
     pub author: Owner,
     pub price: Option<Amount>,
     pub name: String,
+    /// Percent of secondary sales paid to the author
+    pub royalty_percent: u8,
+    pub metadata: CollectionMetadata,
     pub reviewers: HashMap<Owner, Review>,
     pub approved: u16,
     pub rejected: u16,
+    pub status: SubmissionStatus,
+    pub score: u16,
+    pub assignments: HashMap<Owner, Timestamp>,
     pub created_at: Timestamp,
+    pub deadline: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -105,8 +376,40 @@ pub struct Activity {
     pub budget_amount: Amount,
     pub approved: u16,
     pub rejected: u16,
+    pub status: SubmissionStatus,
+    pub assignments: HashMap<Owner, Timestamp>,
     pub created_at: Timestamp,
+    pub deadline: Timestamp,
+    pub reviewers: HashMap<Owner, Review>,
+}
+
+/// Second review round of rejected content or asset, original reviewers are excluded
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Appeal {
+    pub kind: SubmissionKind,
+    pub cid: String,
+    pub author: Owner,
+    pub statement: String,
+    pub bond: Amount,
     pub reviewers: HashMap<Owner, Review>,
+    pub approved: u16,
+    pub rejected: u16,
+    /// Approved if the rejection is overturned, Rejected if it's upheld
+    pub status: SubmissionStatus,
+    pub created_at: Timestamp,
+    pub resolved_at: Option<Timestamp>,
+}
+
+/// Reported content pulled from feed abuse queue
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ContentReport {
+    pub cid: String,
+    pub author: Owner,
+    pub reporters: Vec<Owner>,
+    pub reviewers: HashMap<Owner, Review>,
+    pub upheld: u16,
+    pub dismissed: u16,
+    pub created_at: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
@@ -136,10 +439,12 @@ pub enum Operation {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     },
     RejectContent {
         content_cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     },
     SubmitComment {
         cid: String,
@@ -149,10 +454,12 @@ pub enum Operation {
     ApproveAsset {
         cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     },
     RejectAsset {
         cid: String,
         reason: Option<String>,
+        scores: Option<Vec<RubricScoreInput>>,
     },
     SubmitAsset {
         cid: String,
@@ -160,6 +467,8 @@ pub enum Operation {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
+        metadata: Option<CollectionMetadata>,
     },
     RequestSubscribe,
     SubmitActivity {
@@ -178,6 +487,53 @@ pub enum Operation {
     ActivityApproved {
         activity_id: u64,
     },
+    Report {
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    },
+    UpholdReport {
+        cid: String,
+        reason: Option<String>,
+    },
+    DismissReport {
+        cid: String,
+        reason: Option<String>,
+    },
+    RemoveReviewer {
+        candidate: Owner,
+        reason: Option<String>,
+    },
+    ResumeReviewer {
+        candidate: Owner,
+        reason: Option<String>,
+    },
+    SuspendInactiveReviewers {
+        reviewers: Vec<Owner>,
+    },
+    UpdateQuorumRule {
+        kind: SubmissionKind,
+        rule: QuorumRuleInput,
+    },
+    Appeal {
+        cid: String,
+        statement: String,
+    },
+    ApproveAppeal {
+        cid: String,
+        reason: Option<String>,
+    },
+    RejectAppeal {
+        cid: String,
+        reason: Option<String>,
+    },
+    TakeReviewerExam {
+        answers: Vec<ExamAnswerInput>,
+    },
+    ExpireSubmission {
+        kind: SubmissionKind,
+        id: String,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -211,10 +567,12 @@ pub enum Message {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectContent {
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitComment {
         cid: String,
@@ -224,10 +582,12 @@ pub enum Message {
     ApproveAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitAsset {
         cid: String,
@@ -235,6 +595,8 @@ pub enum Message {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: u8,
+        metadata: CollectionMetadata,
     },
     RequestSubscribe,
     InstantiationArgument {
@@ -253,6 +615,67 @@ pub enum Message {
         activity_id: u64,
         reason: String,
     },
+    SubmitReport {
+        report: ContentReport,
+    },
+    Report {
+        cid: String,
+        reason_category: ReportReason,
+        note: String,
+    },
+    UpholdReport {
+        cid: String,
+        reason: Option<String>,
+    },
+    DismissReport {
+        cid: String,
+        reason: Option<String>,
+    },
+    RemoveReviewer {
+        candidate: Owner,
+        reason: Option<String>,
+    },
+    ResumeReviewer {
+        candidate: Owner,
+        reason: Option<String>,
+    },
+    SuspendInactiveReviewers {
+        reviewers: Vec<Owner>,
+    },
+    SuspendReviewers {
+        reviewers: Vec<Owner>,
+    },
+    UpdateQuorumRule {
+        kind: SubmissionKind,
+        rule: QuorumRule,
+    },
+    AssignReviewers {
+        kind: SubmissionKind,
+        id: String,
+        assignments: HashMap<Owner, Timestamp>,
+    },
+    ExpireSubmission {
+        kind: SubmissionKind,
+        id: String,
+    },
+    ExpireSubmissions {
+        submissions: Vec<PendingSubmission>,
+    },
+    Appeal {
+        cid: String,
+        statement: String,
+    },
+    ApproveAppeal {
+        cid: String,
+        reason: Option<String>,
+    },
+    RejectAppeal {
+        cid: String,
+        reason: Option<String>,
+    },
+    TakeReviewerExam {
+        answers: Vec<ExamAnswer>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -278,6 +701,12 @@ pub enum ReviewError {
     #[error("Invalid activity")]
     InvalidActivity,
 
+    #[error("Invalid report")]
+    InvalidReport,
+
+    #[error("Already resolved")]
+    AlreadyResolved,
+
     #[error("Failed to deserialize BCS bytes")]
     BcsError(#[from] bcs::Error),
 
@@ -296,4 +725,34 @@ pub enum ReviewError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
+
+    #[error("Conflict of interest")]
+    ConflictOfInterest,
+
+    #[error("Not assigned reviewer")]
+    NotAssigned,
+
+    #[error("Invalid score")]
+    InvalidScore,
+
+    #[error("Invalid asset metadata")]
+    InvalidAssetMetadata,
+
+    #[error("Invalid appeal")]
+    InvalidAppeal,
+
+    #[error("Invalid exam")]
+    InvalidExam,
+
+    #[error("Submission not expired")]
+    NotExpired,
+
+    #[error("Insufficient credits")]
+    InsufficientCredits,
+
+    #[error("No eligible reviewer")]
+    NoEligibleReviewer,
 }
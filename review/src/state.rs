@@ -1,55 +1,196 @@
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
 
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, SimpleObject};
 use linera_sdk::{
     base::{Amount, ChainId, Owner, Timestamp},
     views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
 };
 use review::{
-    Activity, Asset, Content, InstantiationArgument, Review as _Review, ReviewError, Reviewer,
+    Activity, Appeal, Asset, Content, ContentReport, ExamAnswer, ExamQuestion,
+    InstantiationArgument, PendingSubmission, QuorumRule, Reputation, Review as _Review,
+    ReviewError, Reviewer, ReviewerExam, Rubric, RubricScore, StatusChange, SubmissionKey,
+    SubmissionKind, SubmissionRecord, SubmissionStatus,
 };
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
+#[graphql(complex)]
 pub struct Review {
     pub reviewers: MapView<Owner, Reviewer>,
     pub reviewer_number: RegisterView<u16>,
     pub reviewer_applications: MapView<Owner, Reviewer>,
+    /// Queried through content_application, which holds back exam questions
+    #[graphql(skip)]
     pub content_applications: MapView<String, Content>,
     pub asset_applications: MapView<String, Asset>,
-    pub content_approved_threshold: RegisterView<u16>,
-    pub content_rejected_threshold: RegisterView<u16>,
-    pub asset_approved_threshold: RegisterView<u16>,
-    pub asset_rejected_threshold: RegisterView<u16>,
-    pub reviewer_approved_threshold: RegisterView<u16>,
-    pub reviewer_rejected_threshold: RegisterView<u16>,
+    pub quorum_rules: MapView<SubmissionKind, QuorumRule>,
     pub activity_applications: MapView<u64, Activity>,
-    pub activity_approved_threshold: RegisterView<u16>,
-    pub activity_rejected_threshold: RegisterView<u16>,
+    pub report_applications: MapView<String, ContentReport>,
+    pub report_upheld_threshold: RegisterView<u16>,
+    pub report_dismissed_threshold: RegisterView<u16>,
+    pub reviewer_inactive_days: RegisterView<u16>,
+    pub conflict_of_interest_days: RegisterView<u16>,
+    pub assigned_reviewers: RegisterView<u16>,
+    pub assignment_timeout_ms: RegisterView<u64>,
+    pub submission_timeout_ms: RegisterView<u64>,
+    pub pending_submissions: MapView<String, PendingSubmission>,
+    pub rubrics: MapView<SubmissionKind, Vec<String>>,
+    pub appeal_quorum: RegisterView<QuorumRule>,
+    pub appeal_bond: RegisterView<Amount>,
+    pub appeals: MapView<String, Appeal>,
+    pub author_submissions: MapView<Owner, Vec<SubmissionKey>>,
+    /// Mixed with every recorded review to rotate reviewer assignment, predictable from the state
+    pub assignment_seed: RegisterView<u64>,
+    pub status_changes: MapView<String, Vec<StatusChange>>,
+    /// Exam of each reviewer applicant, hidden until graded
+    #[graphql(skip)]
+    pub exam_questions: MapView<Owner, Vec<String>>,
+}
+
+const REVIEWER_EXAM_QUESTIONS: usize = 5;
+
+#[ComplexObject]
+impl Review {
+    /// Pending submissions sorted by age, the oldest one first
+    async fn pending_submissions_by_age(
+        &self,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<PendingSubmission>> {
+        let mut submissions = Vec::new();
+        for key in self.pending_submissions.indices().await? {
+            if let Some(submission) = self.pending_submissions.get(&key).await? {
+                submissions.push(submission);
+            }
+        }
+        submissions.sort_by_key(|submission| submission.created_at);
+        Ok(submissions
+            .into_iter()
+            .skip(offset.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .collect())
+    }
+
+    /// Questions of the exam assigned to the reviewer applicant, empty once graded
+    async fn reviewer_exam_questions(
+        &self,
+        candidate: Owner,
+    ) -> async_graphql::Result<Vec<ExamQuestion>> {
+        let cids = self
+            .exam_questions
+            .get(&candidate)
+            .await?
+            .unwrap_or_default();
+        let mut questions = Vec::new();
+        for (question, cid) in cids.iter().enumerate() {
+            if let Some(content) = self.content_applications.get(cid).await? {
+                questions.push(ExamQuestion {
+                    question: question as u16,
+                    title: content.title,
+                    content: content.content,
+                    cover: content.cover,
+                    abbreviation: content.abbreviation,
+                });
+            }
+        }
+        Ok(questions)
+    }
+
+    /// Submissions of all kinds of the author, the latest one first
+    async fn submissions_by_author(
+        &self,
+        author: Owner,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<SubmissionRecord>> {
+        let keys = self
+            .author_submissions
+            .get(&author)
+            .await?
+            .unwrap_or_default();
+        let mut records = Vec::new();
+        for key in keys
+            .iter()
+            .rev()
+            .skip(offset.unwrap_or_default() as usize)
+            .take(limit.unwrap_or(u32::MAX) as usize)
+        {
+            if let Some(record) = self.submission_record(key.kind, &key.id).await? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Cids of content applications, without questions of open reviewer exams
+    async fn content_application_keys(&self) -> async_graphql::Result<Vec<String>> {
+        let exam_cids = self.exam_cids().await?;
+        Ok(self
+            .content_applications
+            .indices()
+            .await?
+            .into_iter()
+            .filter(|cid| !exam_cids.contains(cid))
+            .collect())
+    }
+
+    /// Content application with its reviews, none while it is an open exam question
+    async fn content_application(&self, cid: String) -> async_graphql::Result<Option<Content>> {
+        if self.exam_cids().await?.contains(&cid) {
+            return Ok(None);
+        }
+        Ok(self.content_applications.get(&cid).await?)
+    }
 }
 
 #[allow(dead_code)]
 impl Review {
+    /// Questions of every open reviewer exam, whose consensus stays private until graded
+    async fn exam_cids(&self) -> Result<HashSet<String>, ReviewError> {
+        let mut cids = HashSet::new();
+        for candidate in self.exam_questions.indices().await? {
+            if let Some(questions) = self.exam_questions.get(&candidate).await? {
+                cids.extend(questions);
+            }
+        }
+        Ok(cids)
+    }
+
     pub(crate) async fn instantiate_review(
         &mut self,
         argument: InstantiationArgument,
     ) -> Result<(), ReviewError> {
-        self.content_approved_threshold
-            .set(argument.content_approved_threshold);
-        self.content_rejected_threshold
-            .set(argument.content_rejected_threshold);
-        self.asset_approved_threshold
-            .set(argument.asset_approved_threshold);
-        self.asset_rejected_threshold
-            .set(argument.asset_rejected_threshold);
-        self.reviewer_approved_threshold
-            .set(argument.reviewer_approved_threshold);
-        self.reviewer_rejected_threshold
-            .set(argument.reviewer_rejected_threshold);
-        self.activity_approved_threshold
-            .set(argument.activity_approved_threshold);
-        self.activity_rejected_threshold
-            .set(argument.activity_rejected_threshold);
+        self.quorum_rules
+            .insert(&SubmissionKind::Content, argument.content_quorum)?;
+        self.quorum_rules
+            .insert(&SubmissionKind::Comment, argument.comment_quorum)?;
+        self.quorum_rules
+            .insert(&SubmissionKind::Asset, argument.asset_quorum)?;
+        self.quorum_rules
+            .insert(&SubmissionKind::Activity, argument.activity_quorum)?;
+        self.quorum_rules
+            .insert(&SubmissionKind::Reviewer, argument.reviewer_quorum)?;
+        self.report_upheld_threshold
+            .set(argument.report_upheld_threshold);
+        self.report_dismissed_threshold
+            .set(argument.report_dismissed_threshold);
+        self.reviewer_inactive_days
+            .set(argument.reviewer_inactive_days);
+        self.conflict_of_interest_days
+            .set(argument.conflict_of_interest_days);
+        self.assigned_reviewers.set(argument.assigned_reviewers);
+        self.assignment_timeout_ms
+            .set(argument.assignment_timeout_ms);
+        self.submission_timeout_ms
+            .set(argument.submission_timeout_ms);
+        for rubric in argument.rubrics {
+            self.rubrics.insert(&rubric.kind, rubric.criteria)?;
+        }
+        self.appeal_quorum.set(argument.appeal_quorum);
+        self.appeal_bond.set(argument.appeal_bond);
         Ok(())
     }
 
@@ -57,17 +198,53 @@ impl Review {
         &self,
     ) -> Result<InstantiationArgument, ReviewError> {
         Ok(InstantiationArgument {
-            content_approved_threshold: *self.content_approved_threshold.get(),
-            content_rejected_threshold: *self.content_rejected_threshold.get(),
-            asset_approved_threshold: *self.asset_approved_threshold.get(),
-            asset_rejected_threshold: *self.asset_rejected_threshold.get(),
-            reviewer_approved_threshold: *self.reviewer_approved_threshold.get(),
-            reviewer_rejected_threshold: *self.reviewer_rejected_threshold.get(),
-            activity_approved_threshold: *self.activity_approved_threshold.get(),
-            activity_rejected_threshold: *self.activity_rejected_threshold.get(),
+            content_quorum: self.quorum_rule(SubmissionKind::Content).await?,
+            comment_quorum: self.quorum_rule(SubmissionKind::Comment).await?,
+            asset_quorum: self.quorum_rule(SubmissionKind::Asset).await?,
+            activity_quorum: self.quorum_rule(SubmissionKind::Activity).await?,
+            reviewer_quorum: self.quorum_rule(SubmissionKind::Reviewer).await?,
+            report_upheld_threshold: *self.report_upheld_threshold.get(),
+            report_dismissed_threshold: *self.report_dismissed_threshold.get(),
+            reviewer_inactive_days: *self.reviewer_inactive_days.get(),
+            conflict_of_interest_days: *self.conflict_of_interest_days.get(),
+            assigned_reviewers: *self.assigned_reviewers.get(),
+            assignment_timeout_ms: *self.assignment_timeout_ms.get(),
+            submission_timeout_ms: *self.submission_timeout_ms.get(),
+            rubrics: self.all_rubrics().await?,
+            appeal_quorum: self.appeal_quorum.get().clone(),
+            appeal_bond: *self.appeal_bond.get(),
         })
     }
 
+    pub(crate) async fn quorum_rule(
+        &self,
+        kind: SubmissionKind,
+    ) -> Result<QuorumRule, ReviewError> {
+        Ok(self.quorum_rules.get(&kind).await?.unwrap_or_default())
+    }
+
+    pub(crate) async fn update_quorum_rule(
+        &mut self,
+        kind: SubmissionKind,
+        rule: QuorumRule,
+    ) -> Result<(), ReviewError> {
+        self.quorum_rules.insert(&kind, rule)?;
+        Ok(())
+    }
+
+    // Votes needed to resolve a submission with current active reviewers
+    pub(crate) async fn quorum(
+        &self,
+        kind: SubmissionKind,
+        approved: bool,
+    ) -> Result<u16, ReviewError> {
+        let reviewer_number = *self.reviewer_number.get();
+        Ok(self
+            .quorum_rule(kind)
+            .await?
+            .quorum(approved, reviewer_number))
+    }
+
     pub(crate) async fn genesis_reviewer(
         &mut self,
         chain_id: ChainId,
@@ -84,6 +261,11 @@ impl Review {
                 approved: 1,
                 rejected: 0,
                 created_at: now,
+                reputation: Reputation::new(now),
+                suspended: false,
+                removals: HashMap::default(),
+                resumptions: HashMap::default(),
+                exam: None,
             },
         )?;
         self.reviewer_number.set(1);
@@ -106,6 +288,54 @@ impl Review {
         }
     }
 
+    pub(crate) async fn is_active_reviewer(&self, owner: Owner) -> Result<bool, ReviewError> {
+        match self.reviewers.get(&owner).await? {
+            Some(reviewer) => Ok(!reviewer.suspended),
+            _ => Ok(false),
+        }
+    }
+
+    pub(crate) async fn record_review(
+        &mut self,
+        owner: Owner,
+        submitted_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        match self.reviewers.get(&owner).await? {
+            Some(mut reviewer) => {
+                let latency_ms = now.micros().saturating_sub(submitted_at.micros()) / 1000;
+                reviewer.reputation.reviewed += 1;
+                reviewer.reputation.total_latency_ms += latency_ms;
+                reviewer.reputation.last_active_at = now;
+                reviewer.reputation.refresh();
+                self.reviewers.insert(&owner, reviewer)?;
+                self.mix_assignment_seed(owner, now);
+                Ok(())
+            }
+            _ => Err(ReviewError::InvalidReviewer),
+        }
+    }
+
+    // Reviewers agree with the final outcome get higher reputation
+    pub(crate) async fn settle_reviews(
+        &mut self,
+        reviews: &HashMap<Owner, _Review>,
+        approved: bool,
+    ) -> Result<(), ReviewError> {
+        for (owner, review) in reviews {
+            if let Some(mut reviewer) = self.reviewers.get(owner).await? {
+                if review.approved == approved {
+                    reviewer.reputation.agreed += 1;
+                } else {
+                    reviewer.reputation.disagreed += 1;
+                }
+                reviewer.reputation.refresh();
+                self.reviewers.insert(owner, reviewer)?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn apply_reviewer(
         &mut self,
         chain_id: ChainId,
@@ -130,8 +360,44 @@ impl Review {
                 approved: 0,
                 rejected: 0,
                 created_at: now,
+                reputation: Reputation::default(),
+                suspended: false,
+                removals: HashMap::default(),
+                resumptions: HashMap::default(),
+                exam: None,
             },
         )?;
+        self.add_author_submission(owner, SubmissionKind::Reviewer, owner.to_string(), now)
+            .await?;
+        self.assign_exam_questions(owner).await?;
+        Ok(())
+    }
+
+    // Questions are ordered by the hash of the applicant and the cid, so every chain assigns the
+    // same resolved contents and the applicant can't pick them
+    async fn assign_exam_questions(&mut self, candidate: Owner) -> Result<(), ReviewError> {
+        let mut questions = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            if let Some(content) = self.content_applications.get(&cid).await? {
+                if content.author != candidate
+                    && content.comment_to_cid.is_none()
+                    && (content.status == SubmissionStatus::Approved
+                        || content.status == SubmissionStatus::Rejected)
+                {
+                    let mut hasher = DefaultHasher::new();
+                    candidate.hash(&mut hasher);
+                    cid.hash(&mut hasher);
+                    questions.push((hasher.finish(), cid));
+                }
+            }
+        }
+        questions.sort();
+        let questions = questions
+            .into_iter()
+            .take(REVIEWER_EXAM_QUESTIONS)
+            .map(|(_, cid)| cid)
+            .collect::<Vec<_>>();
+        self.exam_questions.insert(&candidate, questions)?;
         Ok(())
     }
 
@@ -163,7 +429,7 @@ impl Review {
         reviewer: Owner,
         candidate: Owner,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.reviewer_applications.get(&candidate).await? {
@@ -196,6 +462,7 @@ impl Review {
                         reviewer: owner,
                         approved: true,
                         reason,
+                        scores: Vec::new(),
                         created_at: now,
                     },
                 );
@@ -204,13 +471,22 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.reviewer_applications.get(&candidate).await? {
-            Some(reviewer) => {
-                let approved_threshold = *self.reviewer_approved_threshold.get();
+            Some(mut reviewer) => {
+                self.record_review(owner, reviewer.created_at, now).await?;
                 let reviewer_number = *self.reviewer_number.get();
-                if reviewer.approved >= approved_threshold || reviewer.approved >= reviewer_number {
+                if reviewer.approved >= self.quorum(SubmissionKind::Reviewer, true).await? {
+                    reviewer.reputation = Reputation::new(now);
                     self.reviewers.insert(&candidate, reviewer.clone())?;
                     self.reviewer_applications.remove(&candidate)?;
                     self.reviewer_number.set(reviewer_number + 1);
+                    self.record_status_change(
+                        SubmissionKind::Reviewer,
+                        &candidate.to_string(),
+                        SubmissionStatus::Approved,
+                        now,
+                    )
+                    .await?;
+                    self.settle_reviews(&reviewer.reviewers, true).await?;
                     return Ok(Some(reviewer));
                 }
             }
@@ -238,8 +514,9 @@ impl Review {
                     owner,
                     _Review {
                         reviewer: owner,
-                        approved: true,
+                        approved: false,
                         reason,
+                        scores: Vec::new(),
                         created_at: now,
                     },
                 );
@@ -249,9 +526,17 @@ impl Review {
         }
         match self.reviewer_applications.get(&candidate).await? {
             Some(reviewer) => {
-                let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if reviewer.rejected >= rejected_threshold || reviewer.rejected >= reviewer_number {
+                self.record_review(owner, reviewer.created_at, now).await?;
+                if reviewer.rejected >= self.quorum(SubmissionKind::Reviewer, false).await? {
+                    self.reviewer_applications.remove(&candidate)?;
+                    self.record_status_change(
+                        SubmissionKind::Reviewer,
+                        &candidate.to_string(),
+                        SubmissionStatus::Rejected,
+                        now,
+                    )
+                    .await?;
+                    self.settle_reviews(&reviewer.reviewers, false).await?;
                     return Ok(Some(reviewer));
                 }
             }
@@ -265,19 +550,42 @@ impl Review {
         reviewer: Owner,
         content_cid: String,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => match content.reviewers.get(&reviewer) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Some(content) => {
+                if content.status != SubmissionStatus::Pending {
+                    return Err(ReviewError::AlreadyResolved);
+                }
+                match content.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             None => Err(ReviewError::InvalidContent),
         }
     }
 
     pub(crate) async fn submit_content(&mut self, content: Content) -> Result<(), ReviewError> {
+        let kind = match content.comment_to_cid {
+            Some(_) => SubmissionKind::Comment,
+            _ => SubmissionKind::Content,
+        };
+        self.add_pending_submission(PendingSubmission {
+            kind,
+            id: content.cid.clone(),
+            author: content.author,
+            created_at: content.created_at,
+            deadline: content.deadline,
+        })?;
+        self.add_author_submission(
+            content.author,
+            kind,
+            content.cid.clone(),
+            content.created_at,
+        )
+        .await?;
         self.content_applications
             .insert(&content.clone().cid, content)?;
         Ok(())
@@ -288,6 +596,7 @@ impl Review {
         reviewer: Owner,
         content_cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Content>, ReviewError> {
         self.validate_content_review(reviewer, content_cid.clone())
@@ -297,6 +606,11 @@ impl Review {
                 if reviewer == content.author {
                     return Err(ReviewError::InvalidReviewer);
                 }
+                let kind = match content.comment_to_cid {
+                    Some(_) => SubmissionKind::Comment,
+                    _ => SubmissionKind::Content,
+                };
+                self.validate_scores(kind, &scores).await?;
                 content.approved += 1;
                 content.reviewers.insert(
                     reviewer,
@@ -304,6 +618,7 @@ impl Review {
                         reviewer,
                         approved: true,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -312,10 +627,22 @@ impl Review {
             _ => return Err(ReviewError::InvalidContent),
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => {
-                let approved_threshold = *self.reviewer_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if content.approved >= approved_threshold || content.approved >= reviewer_number {
+            Some(mut content) => {
+                self.record_review(reviewer, content.created_at, now)
+                    .await?;
+                let kind = match content.comment_to_cid {
+                    Some(_) => SubmissionKind::Comment,
+                    _ => SubmissionKind::Content,
+                };
+                if content.approved >= self.quorum(kind, true).await? {
+                    content.status = SubmissionStatus::Approved;
+                    content.score = Self::aggregate_score(&content.reviewers);
+                    self.remove_pending_submission(kind, &content_cid)?;
+                    self.record_status_change(kind, &content_cid, content.status, now)
+                        .await?;
+                    self.content_applications
+                        .insert(&content_cid, content.clone())?;
+                    self.settle_reviews(&content.reviewers, true).await?;
                     return Ok(Some(content));
                 }
             }
@@ -329,6 +656,7 @@ impl Review {
         reviewer: Owner,
         content_cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Content>, ReviewError> {
         self.validate_content_review(reviewer, content_cid.clone())
@@ -338,6 +666,11 @@ impl Review {
                 if reviewer == content.author {
                     return Err(ReviewError::InvalidReviewer);
                 }
+                let kind = match content.comment_to_cid {
+                    Some(_) => SubmissionKind::Comment,
+                    _ => SubmissionKind::Content,
+                };
+                self.validate_scores(kind, &scores).await?;
                 content.rejected += 1;
                 content.reviewers.insert(
                     reviewer,
@@ -345,6 +678,7 @@ impl Review {
                         reviewer,
                         approved: false,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -353,10 +687,21 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => {
-                let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if content.rejected >= rejected_threshold || content.rejected >= reviewer_number {
+            Some(mut content) => {
+                self.record_review(reviewer, content.created_at, now)
+                    .await?;
+                let kind = match content.comment_to_cid {
+                    Some(_) => SubmissionKind::Comment,
+                    _ => SubmissionKind::Content,
+                };
+                if content.rejected >= self.quorum(kind, false).await? {
+                    content.status = SubmissionStatus::Rejected;
+                    self.remove_pending_submission(kind, &content_cid)?;
+                    self.record_status_change(kind, &content_cid, content.status, now)
+                        .await?;
+                    self.content_applications
+                        .insert(&content_cid, content.clone())?;
+                    self.settle_reviews(&content.reviewers, false).await?;
                     return Ok(Some(content));
                 }
             }
@@ -370,14 +715,19 @@ impl Review {
         reviewer: Owner,
         cid: String,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => match asset.reviewers.get(&reviewer) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Some(asset) => {
+                if asset.status != SubmissionStatus::Pending {
+                    return Err(ReviewError::AlreadyResolved);
+                }
+                match asset.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             None => Ok(()),
         }
     }
@@ -387,6 +737,7 @@ impl Review {
         reviewer: Owner,
         cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Asset>, ReviewError> {
         self.validate_asset_review(reviewer, cid.clone()).await?;
@@ -395,6 +746,7 @@ impl Review {
                 if reviewer == asset.author {
                     return Err(ReviewError::InvalidReviewer);
                 }
+                self.validate_scores(SubmissionKind::Asset, &scores).await?;
                 asset.approved += 1;
                 asset.reviewers.insert(
                     reviewer,
@@ -402,6 +754,7 @@ impl Review {
                         reviewer,
                         approved: true,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -410,10 +763,16 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => {
-                let approved_threshold = *self.reviewer_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if asset.approved >= approved_threshold || asset.approved >= reviewer_number {
+            Some(mut asset) => {
+                self.record_review(reviewer, asset.created_at, now).await?;
+                if asset.approved >= self.quorum(SubmissionKind::Asset, true).await? {
+                    asset.status = SubmissionStatus::Approved;
+                    asset.score = Self::aggregate_score(&asset.reviewers);
+                    self.remove_pending_submission(SubmissionKind::Asset, &cid)?;
+                    self.record_status_change(SubmissionKind::Asset, &cid, asset.status, now)
+                        .await?;
+                    self.asset_applications.insert(&cid, asset.clone())?;
+                    self.settle_reviews(&asset.reviewers, true).await?;
                     return Ok(Some(asset));
                 }
             }
@@ -427,6 +786,7 @@ impl Review {
         reviewer: Owner,
         cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Asset>, ReviewError> {
         self.validate_asset_review(reviewer, cid.clone()).await?;
@@ -435,6 +795,7 @@ impl Review {
                 if reviewer == asset.author {
                     return Err(ReviewError::InvalidReviewer);
                 }
+                self.validate_scores(SubmissionKind::Asset, &scores).await?;
                 asset.rejected += 1;
                 asset.reviewers.insert(
                     reviewer,
@@ -442,6 +803,7 @@ impl Review {
                         reviewer,
                         approved: false,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -450,10 +812,15 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => {
-                let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if asset.rejected >= rejected_threshold || asset.rejected >= reviewer_number {
+            Some(mut asset) => {
+                self.record_review(reviewer, asset.created_at, now).await?;
+                if asset.rejected >= self.quorum(SubmissionKind::Asset, false).await? {
+                    asset.status = SubmissionStatus::Rejected;
+                    self.remove_pending_submission(SubmissionKind::Asset, &cid)?;
+                    self.record_status_change(SubmissionKind::Asset, &cid, asset.status, now)
+                        .await?;
+                    self.asset_applications.insert(&cid, asset.clone())?;
+                    self.settle_reviews(&asset.reviewers, false).await?;
                     return Ok(Some(asset));
                 }
             }
@@ -466,6 +833,20 @@ impl Review {
         match self.asset_applications.get(&asset.clone().cid).await? {
             Some(_) => return Err(ReviewError::AlreadyExists),
             _ => {
+                self.add_pending_submission(PendingSubmission {
+                    kind: SubmissionKind::Asset,
+                    id: asset.cid.clone(),
+                    author: asset.author,
+                    created_at: asset.created_at,
+                    deadline: asset.deadline,
+                })?;
+                self.add_author_submission(
+                    asset.author,
+                    SubmissionKind::Asset,
+                    asset.cid.clone(),
+                    asset.created_at,
+                )
+                .await?;
                 self.asset_applications.insert(&asset.clone().cid, asset)?;
             }
         }
@@ -479,21 +860,39 @@ impl Review {
         budget_amount: Amount,
         now: Timestamp,
     ) -> Result<(), ReviewError> {
-        match self.activity_applications.get(&activity_id).await {
-            Ok(Some(_)) => Err(ReviewError::AlreadyExists),
-            _ => Ok(self.activity_applications.insert(
-                &activity_id,
-                Activity {
-                    activity_id,
-                    activity_host,
-                    budget_amount,
-                    approved: 0,
-                    rejected: 0,
-                    created_at: now,
-                    reviewers: HashMap::default(),
-                },
-            )?),
+        if let Ok(Some(_)) = self.activity_applications.get(&activity_id).await {
+            return Err(ReviewError::AlreadyExists);
         }
+        let deadline = self.submission_deadline(now);
+        self.add_pending_submission(PendingSubmission {
+            kind: SubmissionKind::Activity,
+            id: activity_id.to_string(),
+            author: activity_host,
+            created_at: now,
+            deadline,
+        })?;
+        self.add_author_submission(
+            activity_host,
+            SubmissionKind::Activity,
+            activity_id.to_string(),
+            now,
+        )
+        .await?;
+        Ok(self.activity_applications.insert(
+            &activity_id,
+            Activity {
+                activity_id,
+                activity_host,
+                budget_amount,
+                approved: 0,
+                rejected: 0,
+                status: SubmissionStatus::Pending,
+                assignments: HashMap::default(),
+                created_at: now,
+                deadline,
+                reviewers: HashMap::default(),
+            },
+        )?)
     }
 
     pub(crate) async fn validate_activity_review(
@@ -501,11 +900,22 @@ impl Review {
         owner: Owner,
         activity_id: u64,
     ) -> Result<(), ReviewError> {
+        if !self.is_active_reviewer(owner).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
         match self.activity_applications.get(&activity_id).await {
-            Ok(Some(activity)) => match activity.reviewers.get(&owner) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Ok(Some(activity)) => {
+                if activity.activity_host == owner {
+                    return Err(ReviewError::InvalidReviewer);
+                }
+                if activity.status != SubmissionStatus::Pending {
+                    return Err(ReviewError::AlreadyResolved);
+                }
+                match activity.reviewers.get(&owner) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             Ok(None) => Err(ReviewError::InvalidActivity),
             Err(err) => Err(ReviewError::ViewError(err)),
         }
@@ -527,17 +937,29 @@ impl Review {
                 reviewer: owner,
                 approved: true,
                 reason,
+                scores: Vec::new(),
                 created_at: now,
             },
         );
         activity.approved += 1;
         self.activity_applications
             .insert(&activity_id, activity.clone())?;
+        self.record_review(owner, activity.created_at, now).await?;
 
-        let approved_threshold = *self.activity_approved_threshold.get();
-        let reviewer_number = *self.reviewer_number.get();
-
-        if activity.approved >= approved_threshold || activity.approved >= reviewer_number {
+        let quorum = self.quorum(SubmissionKind::Activity, true).await?;
+        if activity.approved >= quorum {
+            activity.status = SubmissionStatus::Approved;
+            self.remove_pending_submission(SubmissionKind::Activity, &activity_id.to_string())?;
+            self.record_status_change(
+                SubmissionKind::Activity,
+                &activity_id.to_string(),
+                activity.status,
+                now,
+            )
+            .await?;
+            self.activity_applications
+                .insert(&activity_id, activity.clone())?;
+            self.settle_reviews(&activity.reviewers, true).await?;
             return Ok(Some(activity));
         }
         Ok(None)
@@ -557,19 +979,31 @@ impl Review {
             owner.clone(),
             _Review {
                 reviewer: owner,
-                approved: true,
+                approved: false,
                 reason,
+                scores: Vec::new(),
                 created_at: now,
             },
         );
         activity.rejected += 1;
         self.activity_applications
             .insert(&activity_id, activity.clone())?;
+        self.record_review(owner, activity.created_at, now).await?;
 
-        let rejected_threshold = *self.activity_rejected_threshold.get();
-        let reviewer_number = *self.reviewer_number.get();
-
-        if activity.rejected >= rejected_threshold || activity.rejected >= reviewer_number {
+        let quorum = self.quorum(SubmissionKind::Activity, false).await?;
+        if activity.rejected >= quorum {
+            activity.status = SubmissionStatus::Rejected;
+            self.remove_pending_submission(SubmissionKind::Activity, &activity_id.to_string())?;
+            self.record_status_change(
+                SubmissionKind::Activity,
+                &activity_id.to_string(),
+                activity.status,
+                now,
+            )
+            .await?;
+            self.activity_applications
+                .insert(&activity_id, activity.clone())?;
+            self.settle_reviews(&activity.reviewers, false).await?;
             return Ok(Some(activity));
         }
         Ok(None)
@@ -577,16 +1011,1042 @@ impl Review {
 
     pub(crate) async fn activity_approved(&self, activity_id: u64) -> Result<bool, ReviewError> {
         match self.activity_applications.get(&activity_id).await {
-            Ok(Some(activity)) => {
-                let approved_threshold = *self.activity_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if activity.approved >= approved_threshold || activity.approved >= reviewer_number {
-                    return Ok(true);
-                }
-                Ok(false)
-            }
+            Ok(Some(activity)) => Ok(activity.status == SubmissionStatus::Approved),
             Ok(None) => Err(ReviewError::InvalidActivity),
             Err(err) => Err(ReviewError::ViewError(err)),
         }
     }
+
+    pub(crate) async fn report_exists(&self, cid: String) -> Result<bool, ReviewError> {
+        Ok(self.report_applications.contains_key(&cid).await?)
+    }
+
+    pub(crate) async fn submit_report(&mut self, report: ContentReport) -> Result<(), ReviewError> {
+        if self.report_exists(report.cid.clone()).await? {
+            return Err(ReviewError::AlreadyExists);
+        }
+        self.report_applications
+            .insert(&report.clone().cid, report)?;
+        Ok(())
+    }
+
+    pub(crate) async fn report_author(&self, cid: String) -> Result<Owner, ReviewError> {
+        match self.report_applications.get(&cid).await? {
+            Some(report) => Ok(report.author),
+            None => Err(ReviewError::InvalidReport),
+        }
+    }
+
+    fn mix_assignment_seed(&mut self, owner: Owner, now: Timestamp) {
+        let mut hasher = DefaultHasher::new();
+        self.assignment_seed.get().hash(&mut hasher);
+        owner.hash(&mut hasher);
+        now.micros().hash(&mut hasher);
+        self.assignment_seed.set(hasher.finish());
+    }
+
+    pub(crate) async fn validate_report_review(
+        &self,
+        reviewer: Owner,
+        cid: String,
+    ) -> Result<(), ReviewError> {
+        if !self.is_active_reviewer(reviewer).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        match self.report_applications.get(&cid).await? {
+            Some(report) => {
+                if report.author == reviewer || report.reporters.contains(&reviewer) {
+                    return Err(ReviewError::InvalidReviewer);
+                }
+                match report.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
+            None => Err(ReviewError::InvalidReport),
+        }
+    }
+
+    // Return the report when it's resolved, the report will be removed from applications
+    pub(crate) async fn review_report(
+        &mut self,
+        reviewer: Owner,
+        cid: String,
+        upheld: bool,
+        reason: String,
+        now: Timestamp,
+    ) -> Result<Option<ContentReport>, ReviewError> {
+        self.validate_report_review(reviewer, cid.clone()).await?;
+
+        let mut report = self.report_applications.get(&cid).await?.unwrap();
+        report.reviewers.insert(
+            reviewer,
+            _Review {
+                reviewer,
+                approved: upheld,
+                reason,
+                scores: Vec::new(),
+                created_at: now,
+            },
+        );
+        if upheld {
+            report.upheld += 1;
+        } else {
+            report.dismissed += 1;
+        }
+        self.report_applications.insert(&cid, report.clone())?;
+        self.record_review(reviewer, report.created_at, now).await?;
+
+        let reviewer_number = *self.reviewer_number.get();
+        let resolved = if upheld {
+            report.upheld >= *self.report_upheld_threshold.get() || report.upheld >= reviewer_number
+        } else {
+            report.dismissed >= *self.report_dismissed_threshold.get()
+                || report.dismissed >= reviewer_number
+        };
+        if resolved {
+            self.report_applications.remove(&cid)?;
+            self.settle_reviews(&report.reviewers, upheld).await?;
+            return Ok(Some(report));
+        }
+        Ok(None)
+    }
+
+    // Return the reviewer when it's removed
+    pub(crate) async fn remove_reviewer(
+        &mut self,
+        owner: Owner,
+        candidate: Owner,
+        reason: String,
+        now: Timestamp,
+    ) -> Result<Option<Reviewer>, ReviewError> {
+        if owner == candidate {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        if !self.is_active_reviewer(owner).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let mut reviewer = match self.reviewers.get(&candidate).await? {
+            Some(reviewer) => reviewer,
+            None => return Err(ReviewError::InvalidReviewer),
+        };
+        if reviewer.removals.contains_key(&owner) {
+            return Err(ReviewError::AlreadyReviewed);
+        }
+        reviewer.removals.insert(
+            owner,
+            _Review {
+                reviewer: owner,
+                approved: false,
+                reason,
+                scores: Vec::new(),
+                created_at: now,
+            },
+        );
+
+        let reviewer_number = *self.reviewer_number.get();
+        // Candidate could not vote to remove himself
+        let voters = reviewer_number.saturating_sub(1).max(1);
+        let quorum = self
+            .quorum_rule(SubmissionKind::Reviewer)
+            .await?
+            .quorum(false, voters);
+        if reviewer.removals.len() as u16 >= quorum {
+            self.reviewers.remove(&candidate)?;
+            if !reviewer.suspended {
+                self.reviewer_number.set(reviewer_number - 1);
+            }
+            return Ok(Some(reviewer));
+        }
+        self.reviewers.insert(&candidate, reviewer)?;
+        Ok(None)
+    }
+
+    // Only the given candidates are checked, so callers decide how many reviewers are loaded
+    pub(crate) async fn inactive_reviewers(
+        &self,
+        candidates: Vec<Owner>,
+        now: Timestamp,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let inactive_days = *self.reviewer_inactive_days.get() as u64;
+        if inactive_days == 0 {
+            return Ok(Vec::new());
+        }
+        let inactive_micros = inactive_days * 24 * 3600 * 1_000_000;
+        let mut reviewers = Vec::new();
+        for owner in candidates {
+            let reviewer = match self.reviewers.get(&owner).await? {
+                Some(reviewer) => reviewer,
+                None => continue,
+            };
+            if reviewer.suspended || reviewers.contains(&owner) {
+                continue;
+            }
+            let last_active_at = reviewer.reputation.last_active_at.micros();
+            if now.micros().saturating_sub(last_active_at) >= inactive_micros {
+                reviewers.push(owner);
+            }
+        }
+        Ok(reviewers)
+    }
+
+    // Return reviewers which are really suspended, the last active reviewer won't be suspended
+    pub(crate) async fn suspend_reviewers(
+        &mut self,
+        reviewers: Vec<Owner>,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let mut suspended = Vec::new();
+        for owner in reviewers {
+            let reviewer_number = *self.reviewer_number.get();
+            if reviewer_number <= 1 {
+                break;
+            }
+            if let Some(mut reviewer) = self.reviewers.get(&owner).await? {
+                if reviewer.suspended {
+                    continue;
+                }
+                reviewer.suspended = true;
+                reviewer.resumptions.clear();
+                self.reviewers.insert(&owner, reviewer)?;
+                self.reviewer_number.set(reviewer_number - 1);
+                suspended.push(owner);
+            }
+        }
+        Ok(suspended)
+    }
+
+    // Return the reviewer when enough active reviewers voted to resume it
+    pub(crate) async fn resume_reviewer(
+        &mut self,
+        owner: Owner,
+        candidate: Owner,
+        reason: String,
+        now: Timestamp,
+    ) -> Result<Option<Reviewer>, ReviewError> {
+        if owner == candidate {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        if !self.is_active_reviewer(owner).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let mut reviewer = match self.reviewers.get(&candidate).await? {
+            Some(reviewer) => reviewer,
+            None => return Err(ReviewError::InvalidReviewer),
+        };
+        if !reviewer.suspended {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        if reviewer.resumptions.contains_key(&owner) {
+            return Err(ReviewError::AlreadyReviewed);
+        }
+        reviewer.resumptions.insert(
+            owner,
+            _Review {
+                reviewer: owner,
+                approved: true,
+                reason,
+                scores: Vec::new(),
+                created_at: now,
+            },
+        );
+
+        let reviewer_number = *self.reviewer_number.get();
+        let quorum = self
+            .quorum_rule(SubmissionKind::Reviewer)
+            .await?
+            .quorum(true, reviewer_number);
+        if reviewer.resumptions.len() as u16 >= quorum {
+            reviewer.suspended = false;
+            reviewer.resumptions.clear();
+            reviewer.reputation.last_active_at = now;
+            self.reviewers.insert(&candidate, reviewer.clone())?;
+            self.reviewer_number.set(reviewer_number + 1);
+            return Ok(Some(reviewer));
+        }
+        self.reviewers.insert(&candidate, reviewer)?;
+        Ok(None)
+    }
+
+    pub(crate) async fn active_reviewers(&self) -> Result<Vec<Owner>, ReviewError> {
+        let mut reviewers = Vec::new();
+        for owner in self.reviewers.indices().await? {
+            if self.is_active_reviewer(owner).await? {
+                reviewers.push(owner);
+            }
+        }
+        Ok(reviewers)
+    }
+
+    fn activity_id(id: &str) -> Result<u64, ReviewError> {
+        id.parse::<u64>().map_err(|_| ReviewError::InvalidActivity)
+    }
+
+    pub(crate) async fn submission_author(
+        &self,
+        kind: SubmissionKind,
+        id: String,
+    ) -> Result<Owner, ReviewError> {
+        match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                match self.content_applications.get(&id).await? {
+                    Some(content) => Ok(content.author),
+                    None => Err(ReviewError::InvalidContent),
+                }
+            }
+            SubmissionKind::Asset => match self.asset_applications.get(&id).await? {
+                Some(asset) => Ok(asset.author),
+                None => Err(ReviewError::InvalidContent),
+            },
+            SubmissionKind::Activity => {
+                match self
+                    .activity_applications
+                    .get(&Self::activity_id(&id)?)
+                    .await?
+                {
+                    Some(activity) => Ok(activity.activity_host),
+                    None => Err(ReviewError::InvalidActivity),
+                }
+            }
+            SubmissionKind::Reviewer => Err(ReviewError::InvalidReviewer),
+        }
+    }
+
+    // Assignments and reviews of the submission
+    async fn assignment_of(
+        &self,
+        kind: SubmissionKind,
+        id: String,
+    ) -> Result<(HashMap<Owner, Timestamp>, HashMap<Owner, _Review>), ReviewError> {
+        match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                match self.content_applications.get(&id).await? {
+                    Some(content) => Ok((content.assignments, content.reviewers)),
+                    None => Err(ReviewError::InvalidContent),
+                }
+            }
+            SubmissionKind::Asset => match self.asset_applications.get(&id).await? {
+                Some(asset) => Ok((asset.assignments, asset.reviewers)),
+                None => Err(ReviewError::InvalidContent),
+            },
+            SubmissionKind::Activity => {
+                match self
+                    .activity_applications
+                    .get(&Self::activity_id(&id)?)
+                    .await?
+                {
+                    Some(activity) => Ok((activity.assignments, activity.reviewers)),
+                    None => Err(ReviewError::InvalidActivity),
+                }
+            }
+            SubmissionKind::Reviewer => Err(ReviewError::InvalidReviewer),
+        }
+    }
+
+    pub(crate) async fn update_assignments(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        assignments: HashMap<Owner, Timestamp>,
+    ) -> Result<(), ReviewError> {
+        match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                match self.content_applications.get(&id).await? {
+                    Some(mut content) => {
+                        content.assignments = assignments;
+                        self.content_applications.insert(&id, content)?;
+                        Ok(())
+                    }
+                    None => Err(ReviewError::InvalidContent),
+                }
+            }
+            SubmissionKind::Asset => match self.asset_applications.get(&id).await? {
+                Some(mut asset) => {
+                    asset.assignments = assignments;
+                    self.asset_applications.insert(&id, asset)?;
+                    Ok(())
+                }
+                None => Err(ReviewError::InvalidContent),
+            },
+            SubmissionKind::Activity => {
+                let activity_id = Self::activity_id(&id)?;
+                match self.activity_applications.get(&activity_id).await? {
+                    Some(mut activity) => {
+                        activity.assignments = assignments;
+                        self.activity_applications.insert(&activity_id, activity)?;
+                        Ok(())
+                    }
+                    None => Err(ReviewError::InvalidActivity),
+                }
+            }
+            SubmissionKind::Reviewer => Err(ReviewError::InvalidReviewer),
+        }
+    }
+
+    // Return new assignments if reviewer takes a timed out slot
+    pub(crate) async fn claim_assignment(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        reviewer: Owner,
+        now: Timestamp,
+    ) -> Result<Option<HashMap<Owner, Timestamp>>, ReviewError> {
+        let (mut assignments, reviewers) = self.assignment_of(kind, id.clone()).await?;
+        if assignments.is_empty() || assignments.contains_key(&reviewer) {
+            return Ok(None);
+        }
+        // All assigned reviewers reviewed, the rest votes are open to anyone
+        if assignments
+            .keys()
+            .all(|owner| reviewers.contains_key(owner))
+        {
+            return Ok(None);
+        }
+        let timeout = *self.assignment_timeout_ms.get() * 1000;
+        let timed_out = assignments
+            .iter()
+            .filter(|(owner, assigned_at)| {
+                !reviewers.contains_key(owner)
+                    && now.micros().saturating_sub(assigned_at.micros()) >= timeout
+            })
+            .map(|(owner, _)| *owner)
+            .next();
+        match timed_out {
+            Some(owner) => {
+                assignments.remove(&owner);
+                assignments.insert(reviewer, now);
+                self.update_assignments(kind, id, assignments.clone())
+                    .await?;
+                Ok(Some(assignments))
+            }
+            None => Err(ReviewError::NotAssigned),
+        }
+    }
+
+    // Submissions never expire if the timeout is disabled
+    pub(crate) fn submission_deadline(&self, created_at: Timestamp) -> Timestamp {
+        let timeout = *self.submission_timeout_ms.get();
+        if timeout == 0 {
+            return Timestamp::from(u64::MAX);
+        }
+        Timestamp::from(
+            created_at
+                .micros()
+                .saturating_add(timeout.saturating_mul(1000)),
+        )
+    }
+
+    fn submission_key(kind: SubmissionKind, id: &str) -> String {
+        format!("{:?}:{}", kind, id)
+    }
+
+    pub(crate) fn add_pending_submission(
+        &mut self,
+        submission: PendingSubmission,
+    ) -> Result<(), ReviewError> {
+        let key = Self::submission_key(submission.kind, &submission.id);
+        self.pending_submissions.insert(&key, submission)?;
+        Ok(())
+    }
+
+    pub(crate) fn remove_pending_submission(
+        &mut self,
+        kind: SubmissionKind,
+        id: &str,
+    ) -> Result<(), ReviewError> {
+        self.pending_submissions
+            .remove(&Self::submission_key(kind, id))?;
+        Ok(())
+    }
+
+    // Pending submission of one of the kinds with the id which passed its deadline
+    pub(crate) async fn stale_submission(
+        &self,
+        kinds: &[SubmissionKind],
+        id: &str,
+        now: Timestamp,
+    ) -> Result<Option<PendingSubmission>, ReviewError> {
+        for kind in kinds {
+            let key = Self::submission_key(*kind, id);
+            if let Some(submission) = self.pending_submissions.get(&key).await? {
+                if submission.deadline <= now {
+                    return Ok(Some(submission));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // Rejected submission with an appeal goes back to rejected with the appeal expired
+    async fn expire_appeal(
+        &mut self,
+        cid: &str,
+        now: Timestamp,
+    ) -> Result<Option<Appeal>, ReviewError> {
+        let mut appeal = match self.appeals.get(cid).await? {
+            Some(appeal) => appeal,
+            None => return Ok(None),
+        };
+        if appeal.status != SubmissionStatus::Pending {
+            return Ok(None);
+        }
+        appeal.status = SubmissionStatus::Expired;
+        appeal.resolved_at = Some(now);
+        self.appeals.insert(cid, appeal.clone())?;
+        Ok(Some(appeal))
+    }
+
+    // Return the appeal if an appeal expired, its bond should be refunded
+    pub(crate) async fn expire_submission(
+        &mut self,
+        kind: SubmissionKind,
+        id: String,
+        now: Timestamp,
+    ) -> Result<Option<Appeal>, ReviewError> {
+        self.remove_pending_submission(kind, &id)?;
+        match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                if let Some(mut content) = self.content_applications.get(&id).await? {
+                    match content.status {
+                        SubmissionStatus::Pending => {
+                            content.status = SubmissionStatus::Expired;
+                            self.content_applications.insert(&id, content)?;
+                            self.record_status_change(kind, &id, SubmissionStatus::Expired, now)
+                                .await?;
+                        }
+                        SubmissionStatus::Appealed => {
+                            content.status = SubmissionStatus::Rejected;
+                            self.content_applications.insert(&id, content)?;
+                            self.record_status_change(kind, &id, SubmissionStatus::Rejected, now)
+                                .await?;
+                            return self.expire_appeal(&id, now).await;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            SubmissionKind::Asset => {
+                if let Some(mut asset) = self.asset_applications.get(&id).await? {
+                    match asset.status {
+                        SubmissionStatus::Pending => {
+                            asset.status = SubmissionStatus::Expired;
+                            self.asset_applications.insert(&id, asset)?;
+                            self.record_status_change(kind, &id, SubmissionStatus::Expired, now)
+                                .await?;
+                        }
+                        SubmissionStatus::Appealed => {
+                            asset.status = SubmissionStatus::Rejected;
+                            self.asset_applications.insert(&id, asset)?;
+                            self.record_status_change(kind, &id, SubmissionStatus::Rejected, now)
+                                .await?;
+                            return self.expire_appeal(&id, now).await;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            SubmissionKind::Activity => {
+                let activity_id = Self::activity_id(&id)?;
+                if let Some(mut activity) = self.activity_applications.get(&activity_id).await? {
+                    if activity.status == SubmissionStatus::Pending {
+                        activity.status = SubmissionStatus::Expired;
+                        self.activity_applications.insert(&activity_id, activity)?;
+                        self.record_status_change(kind, &id, SubmissionStatus::Expired, now)
+                            .await?;
+                    }
+                }
+            }
+            SubmissionKind::Reviewer => return Err(ReviewError::InvalidReviewer),
+        }
+        Ok(None)
+    }
+
+    pub(crate) async fn all_rubrics(&self) -> Result<Vec<Rubric>, ReviewError> {
+        let mut rubrics = Vec::new();
+        for kind in self.rubrics.indices().await? {
+            rubrics.push(Rubric {
+                kind,
+                criteria: self.rubrics.get(&kind).await?.unwrap_or_default(),
+            });
+        }
+        Ok(rubrics)
+    }
+
+    // Each criterion of the rubric must be scored once within 1 - 5
+    pub(crate) async fn validate_scores(
+        &self,
+        kind: SubmissionKind,
+        scores: &[RubricScore],
+    ) -> Result<(), ReviewError> {
+        let criteria = self.rubrics.get(&kind).await?.unwrap_or_default();
+        if scores.len() != criteria.len() {
+            return Err(ReviewError::InvalidScore);
+        }
+        for criterion in criteria {
+            match scores.iter().find(|score| score.criterion == criterion) {
+                Some(score) => {
+                    if !(1..=5).contains(&score.score) {
+                        return Err(ReviewError::InvalidScore);
+                    }
+                }
+                None => return Err(ReviewError::InvalidScore),
+            }
+        }
+        Ok(())
+    }
+
+    // Only approving reviewers score the published submission
+    fn aggregate_score(reviews: &HashMap<Owner, _Review>) -> u16 {
+        let scores = reviews
+            .values()
+            .filter(|review| review.approved)
+            .flat_map(|review| review.scores.iter())
+            .map(|score| score.score as u64)
+            .collect::<Vec<_>>();
+        if scores.is_empty() {
+            return 0;
+        }
+        (scores.iter().sum::<u64>() * 100 / scores.len() as u64) as u16
+    }
+
+    async fn submission_reviews(
+        &self,
+        kind: SubmissionKind,
+        cid: &str,
+    ) -> Result<HashMap<Owner, _Review>, ReviewError> {
+        match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                match self.content_applications.get(cid).await? {
+                    Some(content) => Ok(content.reviewers),
+                    None => Err(ReviewError::InvalidContent),
+                }
+            }
+            SubmissionKind::Asset => match self.asset_applications.get(cid).await? {
+                Some(asset) => Ok(asset.reviewers),
+                None => Err(ReviewError::InvalidContent),
+            },
+            _ => Err(ReviewError::InvalidAppeal),
+        }
+    }
+
+    pub(crate) async fn submit_appeal(
+        &mut self,
+        author: Owner,
+        cid: String,
+        statement: String,
+        now: Timestamp,
+    ) -> Result<Appeal, ReviewError> {
+        if self.appeals.get(&cid).await?.is_some() {
+            return Err(ReviewError::AlreadyExists);
+        }
+        let kind = match self.content_applications.get(&cid).await? {
+            Some(mut content) => {
+                if content.author != author || content.status != SubmissionStatus::Rejected {
+                    return Err(ReviewError::InvalidAppeal);
+                }
+                content.status = SubmissionStatus::Appealed;
+                let kind = match content.comment_to_cid {
+                    Some(_) => SubmissionKind::Comment,
+                    _ => SubmissionKind::Content,
+                };
+                self.content_applications.insert(&cid, content)?;
+                kind
+            }
+            None => match self.asset_applications.get(&cid).await? {
+                Some(mut asset) => {
+                    if asset.author != author || asset.status != SubmissionStatus::Rejected {
+                        return Err(ReviewError::InvalidAppeal);
+                    }
+                    asset.status = SubmissionStatus::Appealed;
+                    self.asset_applications.insert(&cid, asset)?;
+                    SubmissionKind::Asset
+                }
+                None => return Err(ReviewError::InvalidAppeal),
+            },
+        };
+        // Nobody could review the appeal, the bond would be locked until expiry
+        if self.appeal_eligible_reviewers(kind, &cid, author).await? == 0 {
+            return Err(ReviewError::NoEligibleReviewer);
+        }
+        self.record_status_change(kind, &cid, SubmissionStatus::Appealed, now)
+            .await?;
+        self.add_pending_submission(PendingSubmission {
+            kind,
+            id: cid.clone(),
+            author,
+            created_at: now,
+            deadline: self.submission_deadline(now),
+        })?;
+        let appeal = Appeal {
+            kind,
+            cid: cid.clone(),
+            author,
+            statement,
+            bond: *self.appeal_bond.get(),
+            reviewers: HashMap::default(),
+            approved: 0,
+            rejected: 0,
+            status: SubmissionStatus::Pending,
+            created_at: now,
+            resolved_at: None,
+        };
+        self.appeals.insert(&cid, appeal.clone())?;
+        Ok(appeal)
+    }
+
+    // Active reviewers except the author and the original reviewers
+    async fn appeal_eligible_reviewers(
+        &self,
+        kind: SubmissionKind,
+        cid: &str,
+        author: Owner,
+    ) -> Result<u16, ReviewError> {
+        let original = self.submission_reviews(kind, cid).await?;
+        Ok(self
+            .active_reviewers()
+            .await?
+            .into_iter()
+            .filter(|reviewer| *reviewer != author && !original.contains_key(reviewer))
+            .count() as u16)
+    }
+
+    // Appeal quorum is larger than the first round, but never more than eligible reviewers
+    pub(crate) async fn appeal_quorum(
+        &self,
+        appeal: &Appeal,
+        approved: bool,
+    ) -> Result<u16, ReviewError> {
+        let eligible = self
+            .appeal_eligible_reviewers(appeal.kind, &appeal.cid, appeal.author)
+            .await?;
+        let first_round = self.quorum(appeal.kind, approved).await?;
+        let required = self
+            .appeal_quorum
+            .get()
+            .quorum(approved, *self.reviewer_number.get())
+            .max(first_round + 1);
+        Ok(required.min(eligible).max(1))
+    }
+
+    pub(crate) async fn validate_appeal_review(
+        &self,
+        reviewer: Owner,
+        cid: String,
+    ) -> Result<(), ReviewError> {
+        if !self.is_active_reviewer(reviewer).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        match self.appeals.get(&cid).await? {
+            Some(appeal) => {
+                if appeal.status != SubmissionStatus::Pending {
+                    return Err(ReviewError::AlreadyResolved);
+                }
+                if appeal.author == reviewer
+                    || self
+                        .submission_reviews(appeal.kind, &cid)
+                        .await?
+                        .contains_key(&reviewer)
+                {
+                    return Err(ReviewError::InvalidReviewer);
+                }
+                match appeal.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
+            None => Err(ReviewError::InvalidAppeal),
+        }
+    }
+
+    // Return the appeal when it's resolved with the final decision of the submission
+    pub(crate) async fn review_appeal(
+        &mut self,
+        reviewer: Owner,
+        cid: String,
+        approved: bool,
+        reason: String,
+        now: Timestamp,
+    ) -> Result<Option<Appeal>, ReviewError> {
+        self.validate_appeal_review(reviewer, cid.clone()).await?;
+
+        let mut appeal = self.appeals.get(&cid).await?.unwrap();
+        appeal.reviewers.insert(
+            reviewer,
+            _Review {
+                reviewer,
+                approved,
+                reason,
+                scores: Vec::new(),
+                created_at: now,
+            },
+        );
+        if approved {
+            appeal.approved += 1;
+        } else {
+            appeal.rejected += 1;
+        }
+        self.record_review(reviewer, appeal.created_at, now).await?;
+
+        let votes = match approved {
+            true => appeal.approved,
+            false => appeal.rejected,
+        };
+        if votes < self.appeal_quorum(&appeal, approved).await? {
+            self.appeals.insert(&cid, appeal)?;
+            return Ok(None);
+        }
+
+        let status = match approved {
+            true => SubmissionStatus::Approved,
+            false => SubmissionStatus::Rejected,
+        };
+        appeal.status = status;
+        appeal.resolved_at = Some(now);
+        self.appeals.insert(&cid, appeal.clone())?;
+        self.remove_pending_submission(appeal.kind, &cid)?;
+        self.record_status_change(appeal.kind, &cid, status, now)
+            .await?;
+        match appeal.kind {
+            SubmissionKind::Asset => {
+                if let Some(mut asset) = self.asset_applications.get(&cid).await? {
+                    asset.status = status;
+                    self.asset_applications.insert(&cid, asset)?;
+                }
+            }
+            _ => {
+                if let Some(mut content) = self.content_applications.get(&cid).await? {
+                    content.status = status;
+                    self.content_applications.insert(&cid, content)?;
+                }
+            }
+        }
+        self.settle_reviews(&appeal.reviewers, approved).await?;
+        Ok(Some(appeal))
+    }
+
+    pub(crate) async fn add_author_submission(
+        &mut self,
+        author: Owner,
+        kind: SubmissionKind,
+        id: String,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        let mut keys = self
+            .author_submissions
+            .get(&author)
+            .await?
+            .unwrap_or_default();
+        self.record_status_change(kind, &id, SubmissionStatus::Pending, now)
+            .await?;
+        keys.push(SubmissionKey { kind, id });
+        self.author_submissions.insert(&author, keys)?;
+        Ok(())
+    }
+
+    pub(crate) async fn record_status_change(
+        &mut self,
+        kind: SubmissionKind,
+        id: &str,
+        status: SubmissionStatus,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        let key = Self::submission_key(kind, id);
+        let mut changes = self.status_changes.get(&key).await?.unwrap_or_default();
+        changes.push(StatusChange {
+            status,
+            changed_at: now,
+        });
+        self.status_changes.insert(&key, changes)?;
+        Ok(())
+    }
+
+    pub(crate) async fn submission_record(
+        &self,
+        kind: SubmissionKind,
+        id: &str,
+    ) -> Result<Option<SubmissionRecord>, ReviewError> {
+        let (status, reviewers, created_at, deadline) = match kind {
+            SubmissionKind::Content | SubmissionKind::Comment => {
+                match self.content_applications.get(id).await? {
+                    Some(content) => (
+                        content.status,
+                        content.reviewers,
+                        content.created_at,
+                        Some(content.deadline),
+                    ),
+                    None => return Ok(None),
+                }
+            }
+            SubmissionKind::Asset => match self.asset_applications.get(id).await? {
+                Some(asset) => (
+                    asset.status,
+                    asset.reviewers,
+                    asset.created_at,
+                    Some(asset.deadline),
+                ),
+                None => return Ok(None),
+            },
+            SubmissionKind::Activity => {
+                match self
+                    .activity_applications
+                    .get(&Self::activity_id(id)?)
+                    .await?
+                {
+                    Some(activity) => (
+                        activity.status,
+                        activity.reviewers,
+                        activity.created_at,
+                        Some(activity.deadline),
+                    ),
+                    None => return Ok(None),
+                }
+            }
+            SubmissionKind::Reviewer => {
+                let owner = id
+                    .parse::<Owner>()
+                    .map_err(|_| ReviewError::InvalidReviewer)?;
+                // Rejected reviewer applications are removed
+                match self.reviewers.get(&owner).await? {
+                    Some(reviewer) => (
+                        SubmissionStatus::Approved,
+                        reviewer.reviewers,
+                        reviewer.created_at,
+                        None,
+                    ),
+                    None => match self.reviewer_applications.get(&owner).await? {
+                        Some(reviewer) => (
+                            SubmissionStatus::Pending,
+                            reviewer.reviewers,
+                            reviewer.created_at,
+                            None,
+                        ),
+                        None => (
+                            SubmissionStatus::Rejected,
+                            HashMap::default(),
+                            Timestamp::default(),
+                            None,
+                        ),
+                    },
+                }
+            }
+        };
+        let status_changes = self
+            .status_changes
+            .get(&Self::submission_key(kind, id))
+            .await?
+            .unwrap_or_default();
+        // Rejected reviewer applications are only left with their status changes
+        let created_at = match status_changes.first() {
+            Some(change) => change.changed_at,
+            None => created_at,
+        };
+        let updated_at = match status_changes.last() {
+            Some(change) => change.changed_at,
+            None => created_at,
+        };
+        let mut reviews = reviewers.into_values().collect::<Vec<_>>();
+        reviews.sort_by_key(|review| review.created_at);
+        let appeal = match kind {
+            SubmissionKind::Content | SubmissionKind::Comment | SubmissionKind::Asset => {
+                self.appeals.get(id).await?
+            }
+            _ => None,
+        };
+        Ok(Some(SubmissionRecord {
+            kind,
+            id: id.to_string(),
+            status,
+            reviews,
+            appeal,
+            created_at,
+            status_changes,
+            updated_at,
+            deadline,
+        }))
+    }
+
+    // Decision mismatch scores 0, otherwise 100 minus distance of average rubric score
+    async fn exam_answer_agreement(
+        &self,
+        candidate: Owner,
+        cid: &str,
+        answer: &ExamAnswer,
+    ) -> Result<u64, ReviewError> {
+        let content = match self.content_applications.get(cid).await? {
+            Some(content) => content,
+            None => return Err(ReviewError::InvalidExam),
+        };
+        if content.author == candidate || content.comment_to_cid.is_some() {
+            return Err(ReviewError::InvalidExam);
+        }
+        let approved = match content.status {
+            SubmissionStatus::Approved => true,
+            SubmissionStatus::Rejected => false,
+            _ => return Err(ReviewError::InvalidExam),
+        };
+        if !answer.scores.is_empty() {
+            self.validate_scores(SubmissionKind::Content, &answer.scores)
+                .await?;
+        }
+        if answer.approved != approved {
+            return Ok(0);
+        }
+        if content.score == 0 || answer.scores.is_empty() {
+            return Ok(100);
+        }
+        let score = answer
+            .scores
+            .iter()
+            .map(|score| score.score as u64)
+            .sum::<u64>()
+            * 100
+            / answer.scores.len() as u64;
+        Ok(100 - score.abs_diff(content.score as u64) / 4)
+    }
+
+    pub(crate) async fn take_reviewer_exam(
+        &mut self,
+        candidate: Owner,
+        answers: Vec<ExamAnswer>,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        let mut reviewer = match self.reviewer_applications.get(&candidate).await? {
+            Some(reviewer) => reviewer,
+            None => return Err(ReviewError::InvalidReviewer),
+        };
+        if reviewer.exam.is_some() {
+            return Err(ReviewError::AlreadyExists);
+        }
+        let questions = self
+            .exam_questions
+            .get(&candidate)
+            .await?
+            .unwrap_or_default();
+        // Every assigned question must be answered once
+        if questions.is_empty() || answers.len() != questions.len() {
+            return Err(ReviewError::InvalidExam);
+        }
+        let mut agreement = 0;
+        for (index, answer) in answers.iter().enumerate() {
+            if answers[..index]
+                .iter()
+                .any(|_answer| _answer.question == answer.question)
+            {
+                return Err(ReviewError::InvalidExam);
+            }
+            let cid = match questions.get(answer.question as usize) {
+                Some(cid) => cid,
+                None => return Err(ReviewError::InvalidExam),
+            };
+            agreement += self.exam_answer_agreement(candidate, cid, answer).await?;
+        }
+        reviewer.exam = Some(ReviewerExam {
+            agreement: (agreement / answers.len() as u64) as u16,
+            questions,
+            answers,
+            taken_at: now,
+        });
+        self.reviewer_applications.insert(&candidate, reviewer)?;
+        self.exam_questions.remove(&candidate)?;
+        Ok(())
+    }
 }